@@ -7,7 +7,8 @@ import {
   Address,
   AddressBalance,
   MessageDto,
-  MessageWrapper
+  MessageWrapper,
+  FoundMessages
 } from './types'
 
 export declare type Api = 'GetHealth' | 'GetInfo' | 'GetTips' | 'PostMessage' | 'PostMessageWithRemotePoW' | 'GetOutput' | 'GetMilestone'
@@ -75,7 +76,7 @@ export declare class Client {
   message(): MessageSender
   getUnspentAddress(seed: string): UnspentAddressGetter
   getAddresses(seed: string): AddressGetter
-  findMessages(indexationKeys: string[], messageIds: string[]): Promise<MessageWrapper[]>
+  findMessages(indexationKeys: string[], messageIds: string[]): Promise<FoundMessages>
   getBalance(seed: string): BalanceGetter
   getAddressBalances(addresses: string[]): Promise<AddressBalance[]>
   retry(messageId: string): Promise<MessageWrapper>
@@ -98,7 +99,7 @@ export declare class Client {
 export declare class MessageFinder {
   index(index: string | number[] | Uint8Array): Promise<string[]>
   data(messageId: string): Promise<MessageWrapper>
-  raw(messageId: string): Promise<string>
+  raw(messageId: string): Promise<number[]>
   children(messageId: string): Promise<string[]>
   metadata(messageId: string): Promise<MessageMetadata>
 }
@@ -98,7 +98,7 @@ MessageGetter.prototype.index = function (index) {
   return messageGetterIndexSetter.apply(this, [Array.from(index)])
 }
 MessageGetter.prototype.data = promisify(MessageGetter.prototype.data)
-MessageGetter.prototype.raw = promisify(MessageGetter.prototype.raw, false)
+MessageGetter.prototype.raw = promisify(MessageGetter.prototype.raw)
 MessageGetter.prototype.children = promisify(MessageGetter.prototype.children)
 MessageGetter.prototype.metadata = promisify(MessageGetter.prototype.metadata)
 
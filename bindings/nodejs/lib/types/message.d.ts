@@ -74,4 +74,24 @@ export declare interface Message {
 export declare interface MessageWrapper {
   messageId: string
   message: Message
+}
+
+export declare type MessageLookupErrorKind = 'NotFound' | 'Pruned' | 'Conversion' | 'Request'
+
+export declare interface MessageLookupError {
+  messageId: string
+  kind: MessageLookupErrorKind
+  error: string
+}
+
+export declare interface IndexLookupError {
+  index: string
+  kind: MessageLookupErrorKind
+  error: string
+}
+
+export declare interface FoundMessages {
+  messages: MessageWrapper[]
+  errors: MessageLookupError[]
+  indexErrors: IndexLookupError[]
 }
\ No newline at end of file
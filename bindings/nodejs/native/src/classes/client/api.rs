@@ -5,7 +5,7 @@ use std::{convert::TryInto, ops::Range, str::FromStr};
 
 use super::MessageDto;
 
-use crate::classes::client::dto::MessageWrapper;
+use crate::classes::client::dto::{FoundMessagesDto, MessageWrapper};
 use iota::{Address, Bech32Address, ClientMiner, MessageBuilder, MessageId, Seed, UTXOInput};
 use neon::prelude::*;
 
@@ -173,15 +173,8 @@ impl Task for ClientTask {
                     indexation_keys,
                     message_ids,
                 } => {
-                    let messages = client.find_messages(&indexation_keys[..], &message_ids[..]).await?;
-                    let message_wrappers: Vec<MessageWrapper> = messages
-                        .into_iter()
-                        .map(|message| MessageWrapper {
-                            message_id: message.id().0,
-                            message,
-                        })
-                        .collect();
-                    serde_json::to_string(&message_wrappers).unwrap()
+                    let found_messages = client.find_messages(&indexation_keys[..], &message_ids[..]).await?;
+                    serde_json::to_string(&FoundMessagesDto::from(found_messages)).unwrap()
                 }
                 Api::GetBalance {
                     seed,
@@ -247,7 +240,10 @@ impl Task for ClientTask {
                     let metadata = client.get_message().metadata(&id).await?;
                     serde_json::to_string(&metadata).unwrap()
                 }
-                Api::GetRawMessage(id) => client.get_message().raw(&id).await?,
+                Api::GetRawMessage(id) => {
+                    let raw = client.get_message().raw(&id).await?;
+                    serde_json::to_string(&raw).unwrap()
+                }
                 Api::GetMessageChildren(id) => {
                     let messages = client.get_message().children(&id).await?;
                     serde_json::to_string(&messages).unwrap()
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use iota::{
+    client::{FoundMessages, MessageLookupError},
     AddressDto, BalanceForAddressResponse as AddressBalancePair, Ed25519Signature, Essence, IndexationPayload, Input,
     Message, MessageId, Output, OutputDto as BeeOutput, OutputResponse as OutputMetadata, Payload, ReferenceUnlock,
     RegularEssence, SignatureUnlock, TransactionPayload, UTXOInput, UnlockBlock,
@@ -20,6 +21,73 @@ pub struct MessageWrapper {
     pub message_id: MessageId,
 }
 
+/// Result of `findMessages`: the found messages and the ones that couldn't be retrieved.
+#[derive(Debug, Serialize)]
+pub struct FoundMessagesDto {
+    pub messages: Vec<MessageWrapper>,
+    pub errors: Vec<MessageLookupErrorDto>,
+    #[serde(rename = "indexErrors")]
+    pub index_errors: Vec<IndexLookupErrorDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MessageLookupErrorDto {
+    #[serde(rename = "messageId")]
+    pub message_id: MessageId,
+    pub kind: &'static str,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexLookupErrorDto {
+    pub index: String,
+    pub kind: &'static str,
+    pub error: String,
+}
+
+/// The name of the error variant, so it can be matched on without parsing the message.
+pub fn message_lookup_error_kind(error: &MessageLookupError) -> &'static str {
+    match error {
+        MessageLookupError::NotFound => "NotFound",
+        MessageLookupError::Pruned => "Pruned",
+        MessageLookupError::Conversion(_) => "Conversion",
+        MessageLookupError::Request(_) => "Request",
+    }
+}
+
+impl From<FoundMessages> for FoundMessagesDto {
+    fn from(found_messages: FoundMessages) -> Self {
+        Self {
+            messages: found_messages
+                .messages
+                .into_iter()
+                .map(|message| MessageWrapper {
+                    message_id: message.id().0,
+                    message,
+                })
+                .collect(),
+            errors: found_messages
+                .errors
+                .into_iter()
+                .map(|(message_id, error)| MessageLookupErrorDto {
+                    message_id,
+                    kind: message_lookup_error_kind(&error),
+                    error: error.to_string(),
+                })
+                .collect(),
+            index_errors: found_messages
+                .index_errors
+                .into_iter()
+                .map(|(index, error)| IndexLookupErrorDto {
+                    index: String::from_utf8_lossy(&index).into_owned(),
+                    kind: message_lookup_error_kind(&error),
+                    error: error.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct MessageRegularEssenceDto {
     inputs: Box<[String]>,
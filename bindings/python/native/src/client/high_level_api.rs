@@ -3,7 +3,7 @@
 
 use crate::client::{
     error::{Error, Result},
-    AddressBalancePair, Client, Input, Message, MessageMetadataResponse, Output,
+    AddressBalancePair, Client, FoundMessages, Input, Message, MessageMetadataResponse, Output,
 };
 use iota::{
     Bech32Address as RustBech32Address, MessageId as RustMessageId, Seed as RustSeed,
@@ -141,8 +141,8 @@ impl Client {
     ///     message_id (str): The identifier of message.
     ///
     /// Returns:
-    ///     raw (str): The returned message string.
-    fn get_message_raw(&self, message_id: &str) -> Result<String> {
+    ///     raw ([int]): The returned message bytes.
+    fn get_message_raw(&self, message_id: &str) -> Result<Vec<u8>> {
         let rt = tokio::runtime::Runtime::new()?;
         Ok(rt.block_on(async {
             self.client
@@ -187,12 +187,13 @@ impl Client {
     ///     message_ids ([str]): The identifier of message.
     ///
     /// Returns:
-    ///     messages ([str]): The returned list of message dict.
+    ///     found_messages (dict): The found messages, along with the message ids and indexation keys that couldn't be
+    ///     retrieved.
     fn find_messages(
         &self,
         indexation_keys: Option<Vec<String>>,
         message_ids: Option<Vec<String>>,
-    ) -> Result<Vec<Message>> {
+    ) -> Result<FoundMessages> {
         let message_ids: Vec<RustMessageId> = message_ids
             .unwrap_or_default()
             .iter()
@@ -204,7 +205,7 @@ impl Client {
                 .find_messages(&indexation_keys.unwrap_or_default()[..], &message_ids[..])
                 .await
         })?;
-        messages.into_iter().map(|message| message.try_into()).collect()
+        messages.try_into()
     }
     fn get_unspent_address(
         &self,
@@ -10,7 +10,7 @@ use iota::{Api, BrokerOptions as RustBrokerOptions, Client as RustClient};
 use pyo3::prelude::*;
 use std::{collections::HashMap, time::Duration};
 use types::{
-    AddressBalancePair, BalanceForAddressResponse, BrokerOptions, InfoResponse, Input, Message,
+    AddressBalancePair, BalanceForAddressResponse, BrokerOptions, FoundMessages, InfoResponse, Input, Message,
     MessageMetadataResponse, MilestoneDto, MilestoneUTXOChanges, Output, OutputResponse, PeerDto, UTXOInput,
     BECH32_HRP,
 };
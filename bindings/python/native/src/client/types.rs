@@ -26,7 +26,7 @@ use iota::{
         },
     },
     builder::NetworkInfo as RustNetworkInfo,
-    client::MilestoneResponse,
+    client::{FoundMessages as RustFoundMessages, MessageLookupError as RustMessageLookupError, MilestoneResponse},
     Address as RustAddress, Ed25519Address as RustEd25519Address, Ed25519Signature as RustEd25519Signature,
     Essence as RustEssence, IndexationPayload as RustIndexationPayload, Input as RustInput, Message as RustMessage,
     MilestonePayloadEssence as RustMilestonePayloadEssence, Output as RustOutput, Payload as RustPayload,
@@ -153,6 +153,27 @@ pub struct Message {
     pub nonce: u64,
 }
 
+#[derive(Debug, Clone, DeriveFromPyObject, DeriveIntoPyObject)]
+pub struct FoundMessages {
+    pub messages: Vec<Message>,
+    pub errors: Vec<MessageLookupError>,
+    pub index_errors: Vec<IndexLookupError>,
+}
+
+#[derive(Debug, Clone, DeriveFromPyObject, DeriveIntoPyObject)]
+pub struct MessageLookupError {
+    pub message_id: String,
+    pub kind: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, DeriveFromPyObject, DeriveIntoPyObject)]
+pub struct IndexLookupError {
+    pub index: String,
+    pub kind: String,
+    pub error: String,
+}
+
 #[derive(Debug, Clone, DeriveFromPyObject, DeriveIntoPyObject)]
 pub struct Payload {
     pub transaction: Option<Vec<Transaction>>,
@@ -725,6 +746,48 @@ impl TryFrom<RustUnlockBlock> for UnlockBlock {
     }
 }
 
+/// The name of the error variant, so it can be matched on without parsing the message.
+fn message_lookup_error_kind(error: &RustMessageLookupError) -> String {
+    match error {
+        RustMessageLookupError::NotFound => "NotFound",
+        RustMessageLookupError::Pruned => "Pruned",
+        RustMessageLookupError::Conversion(_) => "Conversion",
+        RustMessageLookupError::Request(_) => "Request",
+    }
+    .to_string()
+}
+
+impl TryFrom<RustFoundMessages> for FoundMessages {
+    type Error = Error;
+    fn try_from(found_messages: RustFoundMessages) -> Result<Self> {
+        Ok(FoundMessages {
+            messages: found_messages
+                .messages
+                .into_iter()
+                .map(|message| message.try_into())
+                .collect::<Result<Vec<Message>>>()?,
+            errors: found_messages
+                .errors
+                .into_iter()
+                .map(|(message_id, error)| MessageLookupError {
+                    message_id: message_id.to_string(),
+                    kind: message_lookup_error_kind(&error),
+                    error: error.to_string(),
+                })
+                .collect(),
+            index_errors: found_messages
+                .index_errors
+                .into_iter()
+                .map(|(index, error)| IndexLookupError {
+                    index: String::from_utf8_lossy(&index).into_owned(),
+                    kind: message_lookup_error_kind(&error),
+                    error: error.to_string(),
+                })
+                .collect(),
+        })
+    }
+}
+
 impl TryFrom<RustMessage> for Message {
     type Error = Error;
     fn try_from(msg: RustMessage) -> Result<Self> {
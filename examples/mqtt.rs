@@ -9,7 +9,7 @@ use std::sync::{mpsc::channel, Arc, Mutex};
 // like this: iota-core = { path = "../iota-core", features = ["mqtt"] } and uncomment the mqtt example below
 #[tokio::main]
 async fn main() {
-    let mut iota = Client::builder() // Crate a client instance builder
+    let iota = Client::builder() // Crate a client instance builder
         .with_node("https://api.hornet-0.testnet.chrysalis2.com") // Insert the node here
         .unwrap()
         // to use tcp instead
@@ -2,20 +2,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Builder of the Client Instance
-use crate::{client::*, error::*};
+use crate::{
+    cache::ResponseCache,
+    client::*,
+    error::*,
+    node_manager::{
+        NodeAuth, NodeManager, NodeSelectionStrategy, Quorum, RequestLimits, RetryPolicy, DEFAULT_MAX_MILESTONE_LAG,
+        DEFAULT_QUORUM_SIZE, DEFAULT_QUORUM_THRESHOLD,
+    },
+};
 
-use reqwest::Url;
-use tokio::{runtime::Runtime, sync::broadcast::channel};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Certificate, Proxy, Url,
+};
+use tokio::{
+    runtime::{Handle, Runtime},
+    sync::broadcast::channel,
+};
 
 use std::{
     collections::{HashMap, HashSet},
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     time::Duration,
 };
 
+#[cfg(feature = "storage")]
+use std::path::{Path, PathBuf};
+
 const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 const GET_API_TIMEOUT: Duration = Duration::from_millis(2000);
 const NODE_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_PARALLEL_REQUESTS: usize = 10;
 // Interval in seconds when new tips will be requested during PoW
 const TIPS_INTERVAL: u64 = 15;
 
@@ -39,26 +57,116 @@ pub struct NetworkInfo {
     /// Tips request interval during PoW in seconds
     #[serde(rename = "tipsInterval")]
     pub tips_interval: u64,
+    /// Info of the most recent synced node, refreshed by the node syncing
+    #[serde(rename = "nodeInfo", default)]
+    pub node_info: Option<NodeInfoSnapshot>,
+}
+
+/// Options of the HTTP client used for the REST requests to the nodes.
+#[derive(Debug, Clone, Default)]
+pub struct HttpOptions {
+    proxy: Option<String>,
+    headers: Vec<(String, String)>,
+    root_certificates: Vec<Vec<u8>>,
+    user_agent: Option<String>,
+}
+
+impl HttpOptions {
+    /// Creates the default HTTP options.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sends every request through the given HTTP or HTTPS proxy.
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Adds a header sent with every request, e.g. the API key of a hosted node provider.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Trusts an additional root certificate in PEM format.
+    pub fn root_certificate(mut self, pem: &[u8]) -> Self {
+        self.root_certificates.push(pem.to_vec());
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Builds the reqwest client with these options.
+    pub(crate) fn build_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        if !self.headers.is_empty() {
+            let mut headers = HeaderMap::new();
+            for (name, value) in &self.headers {
+                let header_name =
+                    HeaderName::from_bytes(name.as_bytes()).map_err(|_| Error::InvalidHeader(name.clone()))?;
+                let header_value = HeaderValue::from_str(value).map_err(|_| Error::InvalidHeader(name.clone()))?;
+                headers.append(header_name, header_value);
+            }
+            builder = builder.default_headers(headers);
+        }
+        for pem in &self.root_certificates {
+            builder = builder.add_root_certificate(Certificate::from_pem(pem)?);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        Ok(builder.build()?)
+    }
 }
 
 /// Builder to construct client instance with sensible default values
 pub struct ClientBuilder {
     nodes: HashSet<Url>,
+    primary_node: Option<Url>,
+    primary_pow_node: Option<Url>,
+    node_auth: HashMap<Url, NodeAuth>,
     node_sync_interval: Duration,
     node_sync_enabled: bool,
+    runtime_handle: Option<Handle>,
+    max_milestone_lag: u32,
+    node_selection_strategy: NodeSelectionStrategy,
     #[cfg(feature = "mqtt")]
     broker_options: BrokerOptions,
     network_info: NetworkInfo,
+    http_options: HttpOptions,
     request_timeout: Duration,
     api_timeout: HashMap<Api, Duration>,
+    retry_policy: RetryPolicy,
+    request_limits: RequestLimits,
+    quorum: bool,
+    quorum_size: usize,
+    quorum_threshold: usize,
+    cache_capacity: Option<usize>,
+    max_parallel_requests: usize,
+    #[cfg(feature = "storage")]
+    cache_storage_path: Option<PathBuf>,
 }
 
 impl Default for ClientBuilder {
     fn default() -> Self {
         Self {
             nodes: HashSet::new(),
+            primary_node: None,
+            primary_pow_node: None,
+            node_auth: HashMap::new(),
             node_sync_interval: NODE_SYNC_INTERVAL,
             node_sync_enabled: true,
+            runtime_handle: None,
+            max_milestone_lag: DEFAULT_MAX_MILESTONE_LAG,
+            node_selection_strategy: Default::default(),
             #[cfg(feature = "mqtt")]
             broker_options: Default::default(),
             network_info: NetworkInfo {
@@ -68,9 +176,20 @@ impl Default for ClientBuilder {
                 local_pow: true,
                 bech32_hrp: "iota".into(),
                 tips_interval: TIPS_INTERVAL,
+                node_info: None,
             },
+            http_options: Default::default(),
             request_timeout: DEFAULT_REQUEST_TIMEOUT,
             api_timeout: Default::default(),
+            retry_policy: Default::default(),
+            request_limits: Default::default(),
+            quorum: false,
+            quorum_size: DEFAULT_QUORUM_SIZE,
+            quorum_threshold: DEFAULT_QUORUM_THRESHOLD,
+            cache_capacity: None,
+            max_parallel_requests: DEFAULT_MAX_PARALLEL_REQUESTS,
+            #[cfg(feature = "storage")]
+            cache_storage_path: None,
         }
     }
 }
@@ -88,6 +207,38 @@ impl ClientBuilder {
         Ok(self)
     }
 
+    /// Adds an IOTA node that is used for all requests as long as it's healthy, the other nodes are only used when it
+    /// fails. Use `with_node_auth` with the same URL to set its credentials.
+    pub fn with_primary_node(mut self, url: &str) -> Result<Self> {
+        let url = Url::parse(url).map_err(|_| Error::UrlError)?;
+        self.nodes.insert(url.clone());
+        self.primary_node = Some(url);
+        Ok(self)
+    }
+
+    /// Adds an IOTA node that is used for `post_message` with remote PoW as long as it's healthy, and for nothing else.
+    /// Without it, messages with remote PoW are sent to the synced nodes that have the PoW feature enabled.
+    pub fn with_primary_pow_node(mut self, url: &str) -> Result<Self> {
+        let url = Url::parse(url).map_err(|_| Error::UrlError)?;
+        self.nodes.insert(url.clone());
+        self.primary_pow_node = Some(url);
+        Ok(self)
+    }
+
+    /// Adds an IOTA node by its URL, with the credentials sent with the REST requests and the MQTT connection to it.
+    /// The JWT takes precedence over the basic authentication, see `NodeAuth`.
+    pub fn with_node_auth(
+        mut self,
+        url: &str,
+        jwt: Option<&str>,
+        basic_auth_name_pwd: Option<(&str, &str)>,
+    ) -> Result<Self> {
+        let url = Url::parse(url).map_err(|_| Error::UrlError)?;
+        self.nodes.insert(url.clone());
+        self.node_auth.insert(url, NodeAuth::new(jwt, basic_auth_name_pwd));
+        Ok(self)
+    }
+
     /// Adds a list of IOTA nodes by their URLs.
     pub fn with_nodes(mut self, urls: &[&str]) -> Result<Self> {
         for url in urls {
@@ -110,10 +261,35 @@ impl ClientBuilder {
         self
     }
 
+    /// Runs the node syncing as a task on the given runtime, e.g. `Handle::current()` for the runtime of the
+    /// application, instead of on a dedicated runtime thread. Use `Client::shutdown` to stop it.
+    pub fn with_runtime_handle(mut self, handle: Handle) -> Self {
+        self.runtime_handle = Some(handle);
+        self
+    }
+
+    /// Sets how many milestones the solid milestone of a node can be behind the most recent one of the other nodes,
+    /// before the node is removed from the synced node pool. Default: 5
+    pub fn with_max_milestone_lag(mut self, max_milestone_lag: u32) -> Self {
+        self.max_milestone_lag = max_milestone_lag;
+        self
+    }
+
+    /// Sets the strategy used to select a node from the synced node pool for each request.
+    /// Defaults to [`NodeSelectionStrategy::RoundRobin`].
+    pub fn with_node_selection_strategy(mut self, strategy: NodeSelectionStrategy) -> Self {
+        self.node_selection_strategy = strategy;
+        self
+    }
+
     /// Get node list from the node_pool_urls
+    /// The node pools are requested with the HTTP options, so they have to be set before.
     pub async fn with_node_pool_urls(mut self, node_pool_urls: &[String]) -> Result<Self> {
+        let client = self.http_options.build_client()?;
         for pool_url in node_pool_urls {
-            let text: String = reqwest::get(pool_url)
+            let text: String = client
+                .get(pool_url)
+                .send()
                 .await?
                 .text()
                 .await
@@ -154,6 +330,13 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the options of the HTTP client used for all REST requests, like a proxy, additional headers and root
+    /// certificates.
+    pub fn with_http_options(mut self, options: HttpOptions) -> Self {
+        self.http_options = options;
+        self
+    }
+
     /// Sets the default request timeout.
     pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
         self.request_timeout = timeout;
@@ -166,8 +349,75 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the policy to retry failed requests on other nodes of the synced node pool.
+    /// Use [`RetryPolicy::disabled`] to send every request only once.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the limits of the requests sent to each node, like a rate limit and the maximum of requests in flight.
+    pub fn with_request_limits(mut self, request_limits: RequestLimits) -> Self {
+        self.request_limits = request_limits;
+        self
+    }
+
+    /// Enables the quorum for balance and output requests: the same request is sent to multiple synced nodes and the
+    /// response is only returned if enough of them agree on it.
+    pub fn with_quorum(mut self, quorum: bool) -> Self {
+        self.quorum = quorum;
+        self
+    }
+
+    /// Sets the amount of synced nodes queried for a quorum request, at least 1. Default: 3
+    pub fn with_quorum_size(mut self, quorum_size: usize) -> Self {
+        self.quorum_size = quorum_size;
+        self
+    }
+
+    /// Sets the percentage of the queried nodes that need to return the same response for a quorum request, between
+    /// 1 and 100. Default: 66
+    pub fn with_quorum_threshold(mut self, threshold: usize) -> Self {
+        self.quorum_threshold = threshold;
+        self
+    }
+
+    /// Enables the cache of the responses that never change: messages, raw messages, milestones and spent outputs.
+    /// At most `capacity` responses are kept in memory, the least recently used ones are evicted first.
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Persists the cached responses with the storage adapter set for the given path, see `storage::set`.
+    /// The cache needs to be enabled with `with_cache`. Only the responses in memory are bounded by its capacity, the
+    /// persisted ones are never evicted.
+    #[cfg(feature = "storage")]
+    pub fn with_cache_storage<P: AsRef<Path>>(mut self, storage_path: P) -> Self {
+        self.cache_storage_path = Some(storage_path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the maximum of requests sent concurrently by the batch methods, like `find_outputs`, `find_messages` and
+    /// `get_address_balances`. Default: 10
+    pub fn with_max_parallel_requests(mut self, max_parallel_requests: usize) -> Self {
+        self.max_parallel_requests = max_parallel_requests.max(1);
+        self
+    }
+
     /// Build the Client instance.
     pub async fn finish(mut self) -> Result<Client> {
+        if self.quorum {
+            if self.quorum_size == 0 {
+                return Err(Error::InvalidParameter("quorum size must be at least 1".into()));
+            }
+            if self.quorum_threshold == 0 || self.quorum_threshold > 100 {
+                return Err(Error::InvalidParameter(format!(
+                    "quorum threshold is a percentage and must be between 1 and 100, got {}",
+                    self.quorum_threshold
+                )));
+            }
+        }
         let default_testnet_node_pools = vec!["https://giftiota.com/nodes.json".to_string()];
         if self.nodes.is_empty() {
             match self.network_info.network {
@@ -187,74 +437,122 @@ impl ClientBuilder {
         let nodes = self.nodes;
         let node_sync_interval = self.node_sync_interval;
 
-        let (runtime, sync, sync_kill_sender, network_info) = if self.node_sync_enabled {
-            let sync = Arc::new(RwLock::new(HashSet::new()));
-            let sync_ = sync.clone();
-            let network_info_ = network_info.clone();
-            let (sync_kill_sender, sync_kill_receiver) = channel(1);
-            let runtime = std::thread::spawn(move || {
-                let runtime = Runtime::new().unwrap();
-                runtime.block_on(Client::sync_nodes(&sync_, &nodes, &network_info_));
-                Client::start_sync_process(
-                    &runtime,
-                    sync_,
-                    nodes,
-                    node_sync_interval,
-                    network_info_,
-                    sync_kill_receiver,
-                );
-                runtime
-            })
-            .join()
-            .expect("failed to init node syncing process");
-            (Some(runtime), sync, Some(sync_kill_sender), network_info)
-        } else {
-            (None, Arc::new(RwLock::new(nodes)), None, network_info)
-        };
+        let node_selection_strategy = self.node_selection_strategy;
+        let node_auth = self.node_auth;
+        let primary_node = self.primary_node;
+        let primary_pow_node = self.primary_pow_node;
+        let client = self.http_options.build_client()?;
 
+        let api_default_timeouts = [
+            (Api::GetHealth, GET_API_TIMEOUT),
+            (Api::GetInfo, GET_API_TIMEOUT),
+            (Api::GetPeers, GET_API_TIMEOUT),
+            (Api::GetTips, GET_API_TIMEOUT),
+            (Api::PostMessage, GET_API_TIMEOUT),
+            (Api::PostMessageWithRemotePow, DEFAULT_REQUEST_TIMEOUT),
+            (Api::GetOutput, GET_API_TIMEOUT),
+            (Api::GetMilestone, GET_API_TIMEOUT),
+        ];
         let mut api_timeout = HashMap::new();
+        for (api, default_timeout) in api_default_timeouts.iter() {
+            api_timeout.insert(*api, self.api_timeout.remove(api).unwrap_or(*default_timeout));
+        }
+        // The UTXO changes of a milestone used the timeout of `GetMilestone` before they got their own one
+        let milestone_timeout = api_timeout[&Api::GetMilestone];
         api_timeout.insert(
-            Api::GetInfo,
-            self.api_timeout.remove(&Api::GetInfo).unwrap_or(GET_API_TIMEOUT),
-        );
-        api_timeout.insert(
-            Api::GetPeers,
-            self.api_timeout.remove(&Api::GetPeers).unwrap_or(GET_API_TIMEOUT),
-        );
-        api_timeout.insert(
-            Api::GetHealth,
-            self.api_timeout.remove(&Api::GetHealth).unwrap_or(GET_API_TIMEOUT),
-        );
-        api_timeout.insert(
-            Api::GetMilestone,
-            self.api_timeout.remove(&Api::GetMilestone).unwrap_or(GET_API_TIMEOUT),
-        );
-        api_timeout.insert(
-            Api::GetTips,
-            self.api_timeout.remove(&Api::GetTips).unwrap_or(GET_API_TIMEOUT),
-        );
-        api_timeout.insert(
-            Api::PostMessage,
-            self.api_timeout.remove(&Api::PostMessage).unwrap_or(GET_API_TIMEOUT),
-        );
-        api_timeout.insert(
-            Api::PostMessageWithRemotePow,
+            Api::GetMilestoneUtxoChanges,
             self.api_timeout
-                .remove(&Api::PostMessageWithRemotePow)
-                .unwrap_or(DEFAULT_REQUEST_TIMEOUT),
-        );
-        api_timeout.insert(
-            Api::GetOutput,
-            self.api_timeout.remove(&Api::GetOutput).unwrap_or(GET_API_TIMEOUT),
+                .remove(&Api::GetMilestoneUtxoChanges)
+                .unwrap_or(milestone_timeout),
         );
+        // The other APIs use the request timeout, unless a timeout is set for them
+        api_timeout.extend(self.api_timeout.drain());
+        let sync_timeout = api_timeout[&Api::GetInfo];
+
+        let (node_manager, node_syncing) = if self.node_sync_enabled {
+            let node_manager = NodeManager::new(HashSet::new(), node_selection_strategy)
+                .with_auth(node_auth)
+                .with_primary_nodes(primary_node, primary_pow_node)
+                .with_max_milestone_lag(self.max_milestone_lag)
+                .with_request_limits(self.request_limits)
+                .with_nodes(nodes);
+            let node_manager_ = node_manager.clone();
+            let client_ = client.clone();
+            let network_info_ = network_info.clone();
+            let (sync_kill_sender, sync_kill_receiver) = channel(1);
+            match self.runtime_handle {
+                Some(handle) => {
+                    Client::sync_nodes(&client_, &node_manager_, &network_info_, sync_timeout).await;
+                    let sync_handle = Client::start_sync_process(
+                        &handle,
+                        client_,
+                        node_manager_,
+                        node_sync_interval,
+                        sync_timeout,
+                        network_info_,
+                        sync_kill_receiver,
+                    );
+                    let node_syncing = NodeSyncing {
+                        runtime: None,
+                        handle: Some(sync_handle),
+                        kill_sender: sync_kill_sender,
+                    };
+                    (node_manager, Some(node_syncing))
+                }
+                None => {
+                    let runtime = std::thread::spawn(move || {
+                        let runtime = Runtime::new().unwrap();
+                        runtime.block_on(Client::sync_nodes(
+                            &client_,
+                            &node_manager_,
+                            &network_info_,
+                            sync_timeout,
+                        ));
+                        Client::start_sync_process(
+                            runtime.handle(),
+                            client_,
+                            node_manager_,
+                            node_sync_interval,
+                            sync_timeout,
+                            network_info_,
+                            sync_kill_receiver,
+                        );
+                        runtime
+                    })
+                    .join()
+                    .expect("failed to init node syncing process");
+                    let node_syncing = NodeSyncing {
+                        runtime: Some(runtime),
+                        handle: None,
+                        kill_sender: sync_kill_sender,
+                    };
+                    (node_manager, Some(node_syncing))
+                }
+            }
+        } else {
+            (
+                NodeManager::new(nodes.clone(), node_selection_strategy)
+                    .with_nodes(nodes)
+                    .with_auth(node_auth)
+                    .with_primary_nodes(primary_node, primary_pow_node)
+                    .with_request_limits(self.request_limits),
+                None,
+            )
+        };
+
+        let cache = self.cache_capacity.map(ResponseCache::new);
+        #[cfg(feature = "storage")]
+        let cache = match self.cache_storage_path {
+            Some(storage_path) => cache.map(|cache| cache.with_storage(storage_path)),
+            None => cache,
+        };
 
         let client = Client {
-            runtime,
-            sync,
-            sync_kill_sender: sync_kill_sender.map(Arc::new),
-            client: reqwest::Client::new(),
+            node_manager,
+            node_syncing: node_syncing.map(|node_syncing| Arc::new(Mutex::new(node_syncing))),
+            client,
             #[cfg(feature = "mqtt")]
-            mqtt_client: None,
+            mqtt_client: Default::default(),
             #[cfg(feature = "mqtt")]
             mqtt_topic_handlers: Default::default(),
             #[cfg(feature = "mqtt")]
@@ -262,6 +560,16 @@ impl ClientBuilder {
             network_info,
             request_timeout: self.request_timeout,
             api_timeout,
+            retry_policy: self.retry_policy,
+            quorum: match self.quorum {
+                true => Some(Quorum {
+                    size: self.quorum_size,
+                    threshold: self.quorum_threshold,
+                }),
+                false => None,
+            },
+            cache: cache.map(Arc::new),
+            max_parallel_requests: self.max_parallel_requests,
         };
         Ok(client)
     }
@@ -0,0 +1,159 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cache of node responses that never change, like messages and milestones
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+
+#[cfg(feature = "storage")]
+use std::path::PathBuf;
+
+/// Prefix of the keys the responses are persisted with, to keep them apart from other data of the storage adapter.
+#[cfg(feature = "storage")]
+const STORAGE_KEY_PREFIX: &str = "iota-client-cache/";
+
+/// Size-bounded in-memory cache that evicts the least recently used responses, optionally backed by a storage
+/// adapter so the responses survive restarts. The persisted responses are never evicted.
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    capacity: usize,
+    entries: Mutex<LruEntries>,
+    /// Path of the storage adapter the responses are persisted in
+    #[cfg(feature = "storage")]
+    storage_path: Option<PathBuf>,
+}
+
+impl ResponseCache {
+    /// Creates a cache holding at most `capacity` responses in memory.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Default::default(),
+            #[cfg(feature = "storage")]
+            storage_path: None,
+        }
+    }
+
+    /// Persists the responses with the storage adapter set for the given path.
+    #[cfg(feature = "storage")]
+    pub(crate) fn with_storage(mut self, storage_path: PathBuf) -> Self {
+        self.storage_path = Some(storage_path);
+        self
+    }
+
+    /// Gets a cached response, falling back to the storage adapter if it isn't in memory.
+    pub(crate) async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.entries.lock().unwrap().get(key);
+        #[cfg(feature = "storage")]
+        let value = match value {
+            Some(value) => Some(value),
+            None => self.get_stored(key).await,
+        };
+        serde_json::from_str(&value?).ok()
+    }
+
+    /// Caches a response, the cache is best effort so failures are ignored.
+    pub(crate) async fn insert<T: Serialize>(&self, key: &str, value: &T) {
+        if let Ok(value) = serde_json::to_string(value) {
+            #[cfg(feature = "storage")]
+            self.store(key, value.clone()).await;
+            self.entries
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), value, self.capacity);
+        }
+    }
+
+    #[cfg(feature = "storage")]
+    async fn get_stored(&self, key: &str) -> Option<String> {
+        let storage = crate::storage::get(self.storage_path.as_ref()?).await.ok()?;
+        let value = storage
+            .lock()
+            .await
+            .get(&format!("{}{}", STORAGE_KEY_PREFIX, key))
+            .await
+            .ok()?;
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value.clone(), self.capacity);
+        Some(value)
+    }
+
+    #[cfg(feature = "storage")]
+    async fn store(&self, key: &str, value: String) {
+        if let Some(storage_path) = &self.storage_path {
+            if let Ok(storage) = crate::storage::get(storage_path).await {
+                let _ = storage
+                    .lock()
+                    .await
+                    .set(&format!("{}{}", STORAGE_KEY_PREFIX, key), value)
+                    .await;
+            }
+        }
+    }
+}
+
+/// Cached values with the tick of their last use.
+#[derive(Debug, Default)]
+struct LruEntries {
+    tick: u64,
+    values: HashMap<String, (u64, String)>,
+    /// Keys ordered by their last use
+    usage: BTreeMap<u64, String>,
+}
+
+impl LruEntries {
+    fn get(&mut self, key: &str) -> Option<String> {
+        self.tick += 1;
+        let (last_use, value) = self.values.get_mut(key)?;
+        self.usage.remove(last_use);
+        *last_use = self.tick;
+        self.usage.insert(self.tick, key.to_string());
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: String, value: String, capacity: usize) {
+        self.tick += 1;
+        if let Some((last_use, _)) = self.values.insert(key.clone(), (self.tick, value)) {
+            self.usage.remove(&last_use);
+        }
+        self.usage.insert(self.tick, key);
+        while self.values.len() > capacity {
+            let oldest = *self.usage.keys().next().expect("usage contains every cached key");
+            if let Some(key) = self.usage.remove(&oldest) {
+                self.values.remove(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_entry_is_evicted() {
+        let mut entries = LruEntries::default();
+        entries.insert("a".into(), "1".into(), 2);
+        entries.insert("b".into(), "2".into(), 2);
+        assert_eq!(entries.get("a"), Some("1".into()));
+        entries.insert("c".into(), "3".into(), 2);
+        assert_eq!(entries.get("b"), None);
+        assert_eq!(entries.get("a"), Some("1".into()));
+        assert_eq!(entries.get("c"), Some("3".into()));
+    }
+
+    #[tokio::test]
+    async fn cached_responses_are_deserialized() {
+        let cache = ResponseCache::new(10);
+        cache.insert("milestone/1", &vec![1u32, 2, 3]).await;
+        assert_eq!(cache.get::<Vec<u32>>("milestone/1").await, Some(vec![1, 2, 3]));
+        assert_eq!(cache.get::<Vec<u32>>("milestone/2").await, None);
+    }
+}
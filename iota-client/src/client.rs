@@ -5,22 +5,27 @@
 use crate::{
     api::*,
     builder::{ClientBuilder, NetworkInfo},
+    cache::ResponseCache,
     error::*,
     log_request,
     node::*,
+    node_manager::{join_path, retry_after, NodeAuth, NodeEvent, NodeManager, NodeStatus, Quorum, RetryPolicy},
     parse_response, Seed,
 };
 
-use bee_message::prelude::{Bech32Address, Message, MessageBuilder, MessageId, UTXOInput};
+use bee_common::packable::Packable;
+use bee_message::prelude::{
+    Bech32Address, Message, MessageBuilder, MessageId, MilestonePayload, Payload, TransactionId, UTXOInput,
+};
 use bee_pow::providers::{MinerBuilder, Provider as PowProvider, ProviderBuilder as PowProviderBuilder};
 use bee_rest_api::{
     handlers::{
         balance_ed25519::BalanceForAddressResponse, info::InfoResponse as NodeInfo,
-        milestone::MilestoneResponse as MilestoneResponseDto,
+        message_metadata::LedgerInclusionStateDto, milestone::MilestoneResponse as MilestoneResponseDto,
         milestone_utxo_changes::MilestoneUtxoChanges as MilestoneUTXOChanges, output::OutputResponse,
         tips::TipsResponse,
     },
-    types::{MessageDto, PeerDto},
+    types::{MessageDto, PeerDto, ReceiptDto},
 };
 
 use blake2::{
@@ -29,15 +34,18 @@ use blake2::{
 };
 #[cfg(feature = "mqtt")]
 use paho_mqtt::Client as MqttClient;
-use reqwest::{IntoUrl, Url};
+use reqwest::{IntoUrl, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
 #[cfg(feature = "mqtt")]
 use tokio::sync::RwLock as AsyncRwLock;
 use tokio::{
-    runtime::Runtime,
+    runtime::{Handle, Runtime},
     sync::broadcast::{Receiver, Sender},
+    task::JoinHandle,
     time::{sleep, Duration as TokioDuration},
 };
 
+use futures::{stream, StreamExt, TryStreamExt};
 use log::info;
 
 use std::{
@@ -45,8 +53,8 @@ use std::{
     convert::{TryFrom, TryInto},
     hash::Hash,
     str::FromStr,
-    sync::{atomic::AtomicBool, Arc, RwLock},
-    time::Duration,
+    sync::{atomic::AtomicBool, Arc, Mutex, RwLock},
+    time::{Duration, Instant},
 };
 
 #[derive(Debug, Serialize)]
@@ -60,6 +68,175 @@ pub struct MilestoneResponse {
     pub timestamp: u64,
 }
 
+/// Treasury data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreasuryResponse {
+    /// Id of the milestone that created the treasury output.
+    #[serde(rename = "milestoneId")]
+    pub milestone_id: String,
+    /// Amount of the treasury output.
+    pub amount: u64,
+}
+
+/// Status of a transaction, see `Client::get_transaction_status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// The node doesn't know a message including the transaction.
+    Unknown,
+    /// The message including the transaction isn't referenced by a milestone yet.
+    Pending(MessageId),
+    /// The transaction is included in the ledger.
+    Included {
+        /// Message including the transaction.
+        message_id: MessageId,
+        /// Index of the milestone that referenced the message.
+        milestone_index: Option<u32>,
+    },
+    /// The transaction conflicts with the ledger, so it doesn't mutate it.
+    Conflicting {
+        /// Message including the transaction.
+        message_id: MessageId,
+        /// Why the transaction conflicts.
+        reason: Option<ConflictReason>,
+    },
+}
+
+impl TransactionStatus {
+    /// Maps the ledger inclusion state of the message including the transaction to the transaction status.
+    fn from_inclusion_state(
+        message_id: MessageId,
+        ledger_inclusion_state: Option<&LedgerInclusionStateDto>,
+        milestone_index: Option<u32>,
+        conflict_reason: Option<u8>,
+    ) -> Self {
+        match ledger_inclusion_state {
+            Some(LedgerInclusionStateDto::Included) => Self::Included {
+                message_id,
+                milestone_index,
+            },
+            Some(LedgerInclusionStateDto::Conflicting) => Self::Conflicting {
+                message_id,
+                reason: conflict_reason.map(ConflictReason::from),
+            },
+            // The message includes the transaction, so the node is not expected to report it without one
+            Some(LedgerInclusionStateDto::NoTransaction) => Self::Unknown,
+            None => Self::Pending(message_id),
+        }
+    }
+}
+
+/// Reason why a transaction conflicts with the ledger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictReason {
+    /// The referenced UTXO was already spent.
+    InputUtxoAlreadySpent,
+    /// The referenced UTXO was already spent while confirming this milestone.
+    InputUtxoAlreadySpentInThisMilestone,
+    /// The referenced UTXO cannot be found.
+    InputUtxoNotFound,
+    /// The sum of the inputs and output values does not match.
+    InputOutputSumMismatch,
+    /// The unlock block signature is invalid.
+    InvalidSignature,
+    /// The dust allowance of an address is invalid.
+    InvalidDustAllowance,
+    /// The semantic validation failed.
+    SemanticValidationFailed,
+    /// A reason unknown to the client.
+    Other(u8),
+}
+
+impl From<u8> for ConflictReason {
+    fn from(reason: u8) -> Self {
+        match reason {
+            1 => Self::InputUtxoAlreadySpent,
+            2 => Self::InputUtxoAlreadySpentInThisMilestone,
+            3 => Self::InputUtxoNotFound,
+            4 => Self::InputOutputSumMismatch,
+            5 => Self::InvalidSignature,
+            6 => Self::InvalidDustAllowance,
+            255 => Self::SemanticValidationFailed,
+            reason => Self::Other(reason),
+        }
+    }
+}
+
+/// Messages found by `Client::find_messages`, along with the message ids and indexation keys that couldn't be
+/// retrieved.
+#[derive(Debug, Default)]
+pub struct FoundMessages {
+    /// Retrieved messages, the given message ids first and then the ones of the indexation keys.
+    pub messages: Vec<Message>,
+    /// Message ids that couldn't be retrieved, with the reason.
+    pub errors: Vec<(MessageId, MessageLookupError)>,
+    /// Indexation keys whose message ids couldn't be retrieved, with the reason.
+    pub index_errors: Vec<(Vec<u8>, MessageLookupError)>,
+}
+
+/// Reason why a message couldn't be retrieved by `Client::find_messages`.
+#[derive(Debug, thiserror::Error)]
+pub enum MessageLookupError {
+    /// The node doesn't know the message.
+    #[error("Message not found")]
+    NotFound,
+    /// The message is listed under an indexation key, but the node doesn't have its data anymore.
+    #[error("Message pruned")]
+    Pruned,
+    /// The message returned by the node couldn't be converted.
+    #[error("Invalid message: {0}")]
+    Conversion(String),
+    /// The request failed.
+    #[error("Request failed: {0}")]
+    Request(#[source] Error),
+}
+
+impl MessageLookupError {
+    /// Classifies the error of a message request, `indexed` tells if the message is listed under an indexation key.
+    fn from_error(error: Error, indexed: bool) -> Self {
+        match error {
+            Error::ResponseError(404, _) if indexed => Self::Pruned,
+            Error::ResponseError(404, _) => Self::NotFound,
+            Error::InvalidMessageDto(error) => Self::Conversion(error),
+            error => Self::Request(error),
+        }
+    }
+}
+
+/// Typed snapshot of the `/api/v1/info` endpoint of a node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeInfoSnapshot {
+    /// Node name.
+    pub name: String,
+    /// Node version.
+    pub version: String,
+    /// Whether the node is healthy.
+    #[serde(rename = "isHealthy")]
+    pub is_healthy: bool,
+    /// Network the node is part of.
+    #[serde(rename = "networkId")]
+    pub network_id: String,
+    /// Bech32 HRP of the network.
+    #[serde(rename = "bech32HRP")]
+    pub bech32_hrp: String,
+    /// Minimum proof of work score of the network.
+    #[serde(rename = "minPowScore", alias = "minPoWScore")]
+    pub min_pow_score: f64,
+    /// Index of the latest milestone the node knows.
+    #[serde(rename = "latestMilestoneIndex")]
+    pub latest_milestone_index: u32,
+    /// Index of the latest confirmed milestone.
+    #[serde(rename = "solidMilestoneIndex", alias = "confirmedMilestoneIndex")]
+    pub confirmed_milestone_index: u32,
+    /// Index of the milestone up to which the node pruned its data.
+    #[serde(rename = "pruningIndex")]
+    pub pruning_index: u32,
+    /// Features enabled on the node, like `PoW`.
+    pub features: Vec<String>,
+    /// Messages per second, if the node reports them.
+    #[serde(rename = "messagesPerSecond", default)]
+    pub messages_per_second: Option<f64>,
+}
+
 #[cfg(feature = "mqtt")]
 type TopicHandler = Box<dyn Fn(&TopicEvent) + Send + Sync>;
 #[cfg(feature = "mqtt")]
@@ -195,7 +372,7 @@ impl PowProvider for ClientMiner {
 }
 
 /// Each of the node APIs the client uses.
-#[derive(Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Api {
     /// `get_health` API
     GetHealth,
@@ -213,6 +390,36 @@ pub enum Api {
     GetOutput,
     /// `get_milestone` API
     GetMilestone,
+    /// `get_milestone_utxo_changes` API
+    GetMilestoneUtxoChanges,
+    /// `get_message().data()` API
+    GetMessage,
+    /// `get_message().metadata()` API
+    GetMessageMetadata,
+    /// `get_message().raw()` API
+    GetMessageRaw,
+    /// `get_message().children()` API
+    GetMessageChildren,
+    /// `get_message().index()` API
+    GetMessagesByIndex,
+    /// `get_address().balance()` API
+    GetAddressBalance,
+    /// `get_address().outputs()` API
+    GetAddressOutputs,
+    /// `get_receipts` API
+    GetReceipts,
+    /// `get_receipts_migrated_at` API
+    GetReceiptsMigratedAt,
+    /// `get_treasury` API
+    GetTreasury,
+    /// `add_peer` API
+    AddPeer,
+    /// `get_peer` API
+    GetPeer,
+    /// `remove_peer` API
+    RemovePeer,
+    /// `get_included_message` API
+    GetIncludedMessage,
 }
 
 impl FromStr for Api {
@@ -228,25 +435,68 @@ impl FromStr for Api {
             "PostMessageWithRemotePow" => Self::PostMessageWithRemotePow,
             "GetOutput" => Self::GetOutput,
             "GetMilestone" => Self::GetMilestone,
+            "GetMilestoneUtxoChanges" => Self::GetMilestoneUtxoChanges,
+            "GetMessage" => Self::GetMessage,
+            "GetMessageMetadata" => Self::GetMessageMetadata,
+            "GetMessageRaw" => Self::GetMessageRaw,
+            "GetMessageChildren" => Self::GetMessageChildren,
+            "GetMessagesByIndex" => Self::GetMessagesByIndex,
+            "GetAddressBalance" => Self::GetAddressBalance,
+            "GetAddressOutputs" => Self::GetAddressOutputs,
+            "GetReceipts" => Self::GetReceipts,
+            "GetReceiptsMigratedAt" => Self::GetReceiptsMigratedAt,
+            "GetTreasury" => Self::GetTreasury,
+            "AddPeer" => Self::AddPeer,
+            "GetPeer" => Self::GetPeer,
+            "RemovePeer" => Self::RemovePeer,
+            "GetIncludedMessage" => Self::GetIncludedMessage,
             _ => return Err(format!("unknown api kind `{}`", s)),
         };
         Ok(t)
     }
 }
 
+/// The node syncing process, stopped when the last clone of the client is dropped.
+pub(crate) struct NodeSyncing {
+    /// Dedicated runtime of the node syncing, if it doesn't run on the runtime of the application
+    pub(crate) runtime: Option<Runtime>,
+    /// Task of the node syncing, if it runs on the runtime of the application
+    pub(crate) handle: Option<JoinHandle<()>>,
+    /// Sender to stop the node syncing
+    pub(crate) kill_sender: Sender<()>,
+}
+
+impl NodeSyncing {
+    /// Stops the node syncing and returns its task to wait for, if it runs on the runtime of the application.
+    fn stop(&mut self) -> Option<JoinHandle<()>> {
+        // Sending only fails if the syncing already stopped
+        let _ = self.kill_sender.send(());
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+        self.handle.take()
+    }
+}
+
+impl Drop for NodeSyncing {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
 /// An instance of the client using HORNET or Bee URI
+/// Clones share the same node pool, node syncing and MQTT connection.
+#[derive(Clone)]
 pub struct Client {
-    #[allow(dead_code)]
-    pub(crate) runtime: Option<Runtime>,
-    /// Node pool of synced IOTA nodes
-    pub(crate) sync: Arc<RwLock<HashSet<Url>>>,
-    /// Flag to stop the node syncing
-    pub(crate) sync_kill_sender: Option<Arc<Sender<()>>>,
+    /// Node pool of synced IOTA nodes and the node selection
+    pub(crate) node_manager: NodeManager,
+    /// The node syncing process, `None` if the node syncing is disabled
+    pub(crate) node_syncing: Option<Arc<Mutex<NodeSyncing>>>,
     /// A reqwest Client to make Requests with
     pub(crate) client: reqwest::Client,
     /// A MQTT client to subscribe/unsubscribe to topics.
     #[cfg(feature = "mqtt")]
-    pub(crate) mqtt_client: Option<MqttClient>,
+    pub(crate) mqtt_client: Arc<Mutex<Option<MqttClient>>>,
     #[cfg(feature = "mqtt")]
     pub(crate) mqtt_topic_handlers: Arc<AsyncRwLock<TopicHandlerMap>>,
     #[cfg(feature = "mqtt")]
@@ -256,51 +506,59 @@ pub struct Client {
     pub(crate) request_timeout: Duration,
     /// HTTP request timeout for each API call.
     pub(crate) api_timeout: HashMap<Api, Duration>,
+    /// Policy to retry failed requests on other nodes.
+    pub(crate) retry_policy: RetryPolicy,
+    /// Quorum for read requests, disabled if `None`.
+    pub(crate) quorum: Option<Quorum>,
+    /// Cache of the responses that never change, disabled if `None`.
+    pub(crate) cache: Option<Arc<ResponseCache>>,
+    /// Maximum of requests sent concurrently by the batch methods, like `find_outputs`.
+    pub(crate) max_parallel_requests: usize,
 }
 
 impl std::fmt::Debug for Client {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut d = f.debug_struct("Client");
-        d.field("sync", &self.sync).field("client", &self.client);
+        d.field("node_manager", &self.node_manager)
+            .field("client", &self.client);
         #[cfg(feature = "mqtt")]
         d.field("broker_options", &self.broker_options);
         d.field("network_info", &self.network_info).finish()
     }
 }
 
-impl Drop for Client {
-    /// Gracefully shutdown the `Client`
-    fn drop(&mut self) {
-        if let Some(sender) = self.sync_kill_sender.take() {
-            sender.send(()).expect("failed to stop syncing process");
-        }
-
-        if let Some(runtime) = self.runtime.take() {
-            runtime.shutdown_background();
-        }
-
-        #[cfg(feature = "mqtt")]
-        if self.mqtt_client.is_some() {
-            crate::async_runtime::block_on(self.subscriber().disconnect()).expect("failed to disconnect MQTT");
-        }
-    }
-}
-
 impl Client {
     /// Create the builder to instntiate the IOTA Client.
     pub fn builder() -> ClientBuilder {
         ClientBuilder::new()
     }
 
+    /// Stops the node syncing and waits until it's finished, then disconnects MQTT.
+    /// This applies to all clones of the client, the nodes aren't synced anymore afterwards.
+    pub async fn shutdown(&self) -> Result<()> {
+        if let Some(node_syncing) = &self.node_syncing {
+            let sync_handle = node_syncing.lock().unwrap().stop();
+            if let Some(sync_handle) = sync_handle {
+                // The task can only fail if the syncing panicked, which is logged by tokio
+                let _ = sync_handle.await;
+            }
+        }
+
+        #[cfg(feature = "mqtt")]
+        self.subscriber().disconnect().await?;
+        Ok(())
+    }
+
     /// Sync the node lists per node_sync_interval milliseconds
     pub(crate) fn start_sync_process(
-        runtime: &Runtime,
-        sync: Arc<RwLock<HashSet<Url>>>,
-        nodes: HashSet<Url>,
+        runtime: &Handle,
+        client: reqwest::Client,
+        node_manager: NodeManager,
         node_sync_interval: Duration,
+        sync_timeout: Duration,
         network_info: Arc<RwLock<NetworkInfo>>,
         mut kill: Receiver<()>,
-    ) {
+    ) -> JoinHandle<()> {
         let node_sync_interval = TokioDuration::from_nanos(node_sync_interval.as_nanos().try_into().unwrap());
 
         runtime.spawn(async move {
@@ -310,24 +568,57 @@ impl Client {
                             // delay first since the first `sync_nodes` call is made by the builder
                             // to ensure the node list is filled before the client is used
                             sleep(node_sync_interval).await;
-                            Client::sync_nodes(&sync, &nodes, &network_info).await;
+                            Client::sync_nodes(&client, &node_manager, &network_info, sync_timeout).await;
                     } => {}
-                    _ = kill.recv() => {}
+                    _ = kill.recv() => break,
                 }
             }
-        });
+        })
     }
 
+    /// Checks the health and sync state of the configured nodes and updates the synced node pool. `sync_timeout` is
+    /// the timeout of each node info request, so a hanging node doesn't stall the sync.
     pub(crate) async fn sync_nodes(
-        sync: &Arc<RwLock<HashSet<Url>>>,
-        nodes: &HashSet<Url>,
+        client: &reqwest::Client,
+        node_manager: &NodeManager,
         network_info: &Arc<RwLock<NetworkInfo>>,
+        sync_timeout: Duration,
     ) {
+        // A sync that runs at the same time could otherwise replace the pool with an older result
+        let _sync_guard = node_manager.lock_sync().await;
+        let nodes = node_manager.nodes();
         let mut synced_nodes = HashSet::new();
-        let mut network_nodes: HashMap<String, Vec<(NodeInfo, Url)>> = HashMap::new();
-        for node_url in nodes {
+        let mut pow_nodes = HashSet::new();
+        let mut network_nodes: HashMap<String, Vec<(NodeInfoSnapshot, Url)>> = HashMap::new();
+        for node_url in &nodes {
             // Put the healthy node url into the network_nodes
-            if let Ok(info) = Client::get_node_info(node_url.clone()).await {
+            let started = Instant::now();
+            let info = Client::request_node_info::<_, NodeInfoSnapshot>(
+                client,
+                node_url.clone(),
+                node_manager.auth(node_url),
+                Some(sync_timeout),
+            )
+            .await;
+            let mut status = NodeStatus::new(node_url.clone());
+            match &info {
+                Ok(info) => {
+                    let latency = started.elapsed();
+                    node_manager.record_success(node_url, latency);
+                    status.healthy = info.is_healthy;
+                    status.network_id = Some(info.network_id.clone());
+                    status.latest_milestone_index = Some(info.latest_milestone_index);
+                    status.solid_milestone_index = Some(info.confirmed_milestone_index);
+                    status.latency = Some(latency);
+                    status.pow = info.features.contains(&"PoW".to_string());
+                }
+                Err(e) => {
+                    node_manager.record_error(node_url);
+                    status.last_error = Some(e.to_string());
+                }
+            }
+            node_manager.set_status(status);
+            if let Ok(info) = info {
                 if info.is_healthy {
                     match network_nodes.get_mut(&info.network_id) {
                         Some(network_id_entry) => {
@@ -356,29 +647,233 @@ impl Client {
             }
         }
         if let Some(nodes) = network_nodes.get(most_nodes.0) {
+            // Nodes can be healthy but still lag behind, so compare them with the most recent node of the network
+            let latest_node_info = nodes
+                .iter()
+                .map(|(info, _)| info)
+                .max_by_key(|info| info.confirmed_milestone_index);
+            let highest_solid_milestone_index = latest_node_info
+                .map(|info| info.confirmed_milestone_index)
+                .unwrap_or_default();
+            network_info.write().unwrap().node_info = latest_node_info.cloned();
             for (info, node_url) in nodes.iter() {
+                let milestone_lag = highest_solid_milestone_index.saturating_sub(info.confirmed_milestone_index);
+                if milestone_lag > node_manager.max_milestone_lag {
+                    info!("Node {} is {} milestones behind, skipping it", node_url, milestone_lag);
+                    continue;
+                }
                 let mut client_network_info = network_info.write().unwrap();
                 client_network_info.network_id = Some(hash_network(&info.network_id));
                 client_network_info.min_pow_score = info.min_pow_score;
                 client_network_info.bech32_hrp = info.bech32_hrp.clone();
-                if !client_network_info.local_pow {
-                    if info.features.contains(&"PoW".to_string()) {
-                        synced_nodes.insert(node_url.clone());
-                    }
-                } else {
-                    synced_nodes.insert(node_url.clone());
+                if info.features.contains(&"PoW".to_string()) {
+                    pow_nodes.insert(node_url.clone());
                 }
+                synced_nodes.insert(node_url.clone());
             }
+        } else {
+            // No node synced, so there is no recent node info anymore
+            network_info.write().unwrap().node_info = None;
         }
 
         // Update the sync list
-        *sync.write().unwrap() = synced_nodes;
+        node_manager.set_synced_nodes(synced_nodes, pow_nodes);
+    }
+
+    /// Sends a request built by `request` to a node of the synced node pool.
+    /// Timeouts, connection errors and responses with a retryable status code are retried on the next node according
+    /// to the retry policy, and the failing node is quarantined. Latencies and failures are recorded in the node
+    /// statistics.
+    pub(crate) async fn send_request<F>(&self, path: &str, query: Option<&str>, request: F) -> Result<Response>
+    where
+        F: Fn(Url) -> RequestBuilder,
+    {
+        self.send_request_to_pool(false, true, path, query, request).await
+    }
+
+    /// Sends a request built by `request` to a synced node, one that supports remote PoW if `pow` is set.
+    /// A request that isn't `idempotent` isn't retried after a timeout, since the node might have processed it.
+    async fn send_request_to_pool<F>(
+        &self,
+        pow: bool,
+        idempotent: bool,
+        path: &str,
+        query: Option<&str>,
+        request: F,
+    ) -> Result<Response>
+    where
+        F: Fn(Url) -> RequestBuilder,
+    {
+        let retry_policy = &self.retry_policy;
+        let mut tried_nodes = Vec::new();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let node = match pow {
+                true => self.node_manager.select_pow_node(&tried_nodes),
+                false => self.node_manager.select_node(&tried_nodes),
+            }
+            .ok_or(Error::SyncedNodePoolEmpty)?;
+            let result = self.send_request_to_node(&node, path, query, &request).await;
+            let rate_limited = matches!(&result, Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS);
+            let retryable = match &result {
+                Ok(resp) => rate_limited || retry_policy.is_retryable_status(resp.status().as_u16()),
+                // The request wasn't sent if the connection failed
+                Err(e) => e.is_connect() || (idempotent && (e.is_timeout() || e.is_request())),
+            };
+            if !retryable || attempt >= retry_policy.max_attempts {
+                return Ok(result?);
+            }
+
+            info!("Request to {} failed, retrying on the next node", node);
+            // Rate limited nodes are already skipped for as long as they asked for by `send_request_to_node`
+            if !rate_limited {
+                self.node_manager.quarantine(&node, retry_policy.quarantine_duration);
+            }
+            tried_nodes.push(node);
+            sleep(retry_policy.backoff_delay(attempt)).await;
+        }
+    }
+
+    /// Gets a response from the cache, if the cache is enabled.
+    pub(crate) async fn get_cached<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        match &self.cache {
+            Some(cache) => cache.get(key).await,
+            None => None,
+        }
+    }
+
+    /// Caches a response that never changes, if the cache is enabled.
+    pub(crate) async fn cache_response<T: serde::Serialize>(&self, key: &str, value: &T) {
+        if let Some(cache) = &self.cache {
+            cache.insert(key, value).await;
+        }
+    }
+
+    /// Sends a GET request with the timeout of the given API to a node of the synced node pool.
+    pub(crate) async fn get_request(&self, api: Api, path: &str, query: Option<&str>) -> Result<Response> {
+        let timeout = self.get_timeout(api);
+        self.send_request(path, query, |url| self.client.get(url).timeout(timeout))
+            .await
+    }
+
+    /// Sends a GET request with the timeout of the given API to multiple synced nodes, see `send_quorum_request`.
+    pub(crate) async fn get_quorum_request<T: DeserializeOwned>(
+        &self,
+        api: Api,
+        path: &str,
+        query: Option<&str>,
+    ) -> Result<T> {
+        let timeout = self.get_timeout(api);
+        self.send_quorum_request(path, query, |url| self.client.get(url).timeout(timeout))
+            .await
+    }
+
+    /// Sends a request built by `request` to the given node and records its latency or failure in the node
+    /// statistics. A node responding with status 429 is skipped by the node selection until its `Retry-After` elapsed.
+    async fn send_request_to_node<F>(
+        &self,
+        node: &Url,
+        path: &str,
+        query: Option<&str>,
+        request: &F,
+    ) -> reqwest::Result<Response>
+    where
+        F: Fn(Url) -> RequestBuilder,
+    {
+        let mut url = join_path(node, path);
+        url.set_query(query);
+
+        let mut request = request(url);
+        if let Some(auth) = self.node_manager.auth(node) {
+            request = auth.apply(request);
+        }
+
+        let _permit = self.node_manager.acquire_request_slot(node).await;
+        let started = Instant::now();
+        let result = request.send().await;
+        match &result {
+            Ok(resp)
+                if !resp.status().is_server_error()
+                    && resp.status() != StatusCode::TOO_MANY_REQUESTS
+                    && !self.retry_policy.is_retryable_status(resp.status().as_u16()) =>
+            {
+                self.node_manager.record_success(node, started.elapsed())
+            }
+            _ => self.node_manager.record_error(node),
+        }
+        if let Ok(resp) = &result {
+            if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+                let max_delay = self.retry_policy.quarantine_duration;
+                let delay = retry_after(resp, max_delay).unwrap_or_else(|| self.retry_policy.backoff_delay(1));
+                self.node_manager.quarantine(node, delay);
+            }
+        }
+        result
+    }
+
+    /// Sends the same GET request built by `request` to multiple synced nodes and returns the response `data` once
+    /// enough nodes agree on it, according to the quorum size and threshold.
+    pub(crate) async fn send_quorum_request<T, F>(&self, path: &str, query: Option<&str>, request: F) -> Result<T>
+    where
+        T: DeserializeOwned,
+        F: Fn(Url) -> RequestBuilder,
+    {
+        let quorum = self
+            .quorum
+            .as_ref()
+            .ok_or_else(|| Error::MissingParameter("quorum".into()))?;
+        let mut nodes = Vec::new();
+        while nodes.len() < quorum.size {
+            match self.node_manager.select_node(&nodes) {
+                Some(node) if !nodes.contains(&node) => nodes.push(node),
+                _ => break,
+            }
+        }
+        if nodes.len() < quorum.size {
+            return Err(Error::QuorumPoolSizeError(nodes.len(), quorum.size));
+        }
+
+        let responses = futures::future::join_all(
+            nodes
+                .iter()
+                .map(|node| self.quorum_node_response(node, path, query, &request)),
+        )
+        .await;
+
+        let responses = nodes
+            .into_iter()
+            .zip(responses)
+            .map(|(node, response)| {
+                if let Err(e) = &response {
+                    info!("Quorum request to {} failed: {}", node, e);
+                }
+                (node, response)
+            })
+            .collect();
+        Ok(serde_json::from_value(quorum.agreed_data(responses)?)?)
     }
 
-    /// Get a node candidate from the synced node pool.
-    pub(crate) fn get_node(&self) -> Result<Url> {
-        let pool = self.sync.read().unwrap();
-        Ok(pool.iter().next().ok_or(Error::SyncedNodePoolEmpty)?.clone())
+    /// Gets the response `data` of a single node for a quorum request.
+    async fn quorum_node_response<F>(
+        &self,
+        node: &Url,
+        path: &str,
+        query: Option<&str>,
+        request: &F,
+    ) -> Result<serde_json::Value>
+    where
+        F: Fn(Url) -> RequestBuilder,
+    {
+        let resp = self.send_request_to_node(node, path, query, request).await?;
+        #[derive(Debug, Serialize, Deserialize)]
+        struct DataWrapper {
+            data: serde_json::Value,
+        }
+        log_request!("GET", path, resp);
+        parse_response!(resp, 200 => {
+            Ok(resp.json::<DataWrapper>().await?.data)
+        })
     }
 
     /// Gets the network id of the node we're connecting to.
@@ -417,6 +912,12 @@ impl Client {
         Ok(self.get_network_info().await?.min_pow_score)
     }
 
+    /// Returns the info of the most recent synced node, as of the last node syncing.
+    /// `None` if the nodes weren't synced yet or the node syncing is disabled.
+    pub fn get_node_info_snapshot(&self) -> Option<NodeInfoSnapshot> {
+        self.network_info.read().unwrap().node_info.clone()
+    }
+
     /// returns the tips interval
     pub fn get_tips_interval(&self) -> u64 {
         self.network_info.read().unwrap().tips_interval
@@ -427,13 +928,84 @@ impl Client {
         self.network_info.read().unwrap().local_pow
     }
 
+    /// Adds a node to the node pool of the running client. With the node syncing enabled, the nodes are synced right
+    /// away, so the node is only used if it's healthy.
+    pub async fn add_node(&self, url: &str) -> Result<()> {
+        let url = Url::parse(url).map_err(|_| Error::UrlError)?;
+        self.node_manager.add_node(url);
+        self.update_node_pool().await;
+        Ok(())
+    }
+
+    /// Adds a node with the credentials sent with the REST requests and the MQTT connection to it, like
+    /// `ClientBuilder::with_node_auth`, see `add_node`.
+    pub async fn add_node_with_auth(
+        &self,
+        url: &str,
+        jwt: Option<&str>,
+        basic_auth_name_pwd: Option<(&str, &str)>,
+    ) -> Result<()> {
+        let url = Url::parse(url).map_err(|_| Error::UrlError)?;
+        self.node_manager
+            .set_auth(url.clone(), NodeAuth::new(jwt, basic_auth_name_pwd));
+        self.node_manager.add_node(url);
+        self.update_node_pool().await;
+        Ok(())
+    }
+
+    /// Removes a node from the node pool of the running client.
+    pub fn remove_node(&self, url: &str) -> Result<()> {
+        let url = Url::parse(url).map_err(|_| Error::UrlError)?;
+        self.node_manager.remove_node(&url);
+        Ok(())
+    }
+
+    /// Replaces the nodes of the running client. With the node syncing enabled, the new nodes are synced right away.
+    pub async fn replace_nodes(&self, urls: &[&str]) -> Result<()> {
+        let mut nodes = HashSet::new();
+        for url in urls {
+            nodes.insert(Url::parse(url).map_err(|_| Error::UrlError)?);
+        }
+        self.node_manager.replace_nodes(nodes);
+        self.update_node_pool().await;
+        Ok(())
+    }
+
+    /// Updates the synced node pool after the configured nodes changed.
+    async fn update_node_pool(&self) {
+        if self.node_syncing.is_some() {
+            Client::sync_nodes(
+                &self.client,
+                &self.node_manager,
+                &self.network_info,
+                self.get_timeout(Api::GetInfo),
+            )
+            .await;
+        } else {
+            // Every node is considered healthy without the node syncing
+            let nodes = self.node_manager.nodes();
+            self.node_manager.set_synced_nodes(nodes.clone(), nodes);
+        }
+    }
+
+    /// Returns the last sync result of every node, sorted by URL.
+    /// Nodes are only listed after they have been synced, so the list is empty if the node syncing is disabled.
+    pub fn get_node_pool_status(&self) -> Vec<NodeStatus> {
+        self.node_manager.status()
+    }
+
+    /// Returns a receiver of the events sent when nodes join or leave the synced node pool.
+    pub fn node_pool_events(&self) -> Receiver<NodeEvent> {
+        self.node_manager.subscribe()
+    }
+
     ///////////////////////////////////////////////////////////////////////
     // MQTT API
     //////////////////////////////////////////////////////////////////////
 
     /// Returns a handle to the MQTT topics manager.
     #[cfg(feature = "mqtt")]
-    pub fn subscriber(&mut self) -> MqttManager<'_> {
+    pub fn subscriber(&self) -> MqttManager<'_> {
         MqttManager::new(self)
     }
 
@@ -441,14 +1013,13 @@ impl Client {
     // Node API
     //////////////////////////////////////////////////////////////////////
 
-    fn get_timeout(&self, api: Api) -> Duration {
+    pub(crate) fn get_timeout(&self, api: Api) -> Duration {
         *self.api_timeout.get(&api).unwrap_or(&self.request_timeout)
     }
 
     /// GET /health endpoint
     pub async fn get_node_health<T: IntoUrl>(url: T) -> Result<bool> {
-        let mut url = url.into_url()?;
-        url.set_path("health");
+        let url = join_path(&url.into_url()?, "health");
         let resp = reqwest::get(url).await?;
 
         match resp.status().as_u16() {
@@ -459,14 +1030,8 @@ impl Client {
 
     /// GET /health endpoint
     pub async fn get_health(&self) -> Result<bool> {
-        let mut url = self.get_node()?;
-        url.set_path("health");
-        let resp = self
-            .client
-            .get(url)
-            .timeout(self.get_timeout(Api::GetHealth))
-            .send()
-            .await?;
+        let path = "health";
+        let resp = self.get_request(Api::GetHealth, path, None).await?;
 
         match resp.status().as_u16() {
             200 => Ok(true),
@@ -476,31 +1041,40 @@ impl Client {
 
     /// GET /api/v1/info endpoint
     pub async fn get_node_info<T: IntoUrl>(url: T) -> Result<NodeInfo> {
-        let mut url = url.into_url()?;
+        Self::request_node_info(&reqwest::Client::new(), url, None, None).await
+    }
+
+    /// GET /api/v1/info endpoint of a node with the given reqwest client, credentials and timeout
+    pub(crate) async fn request_node_info<T: IntoUrl, I: DeserializeOwned>(
+        client: &reqwest::Client,
+        url: T,
+        auth: Option<NodeAuth>,
+        timeout: Option<Duration>,
+    ) -> Result<I> {
         let path = "api/v1/info";
-        url.set_path(path);
-        let resp = reqwest::get(url).await?;
-        #[derive(Debug, Serialize, Deserialize)]
-        struct NodeInfoWrapper {
-            data: NodeInfo,
+        let url = join_path(&url.into_url()?, path);
+        let mut request = client.get(url);
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        if let Some(auth) = auth {
+            request = auth.apply(request);
+        }
+        let resp = request.send().await?;
+        #[derive(Debug, Deserialize)]
+        struct NodeInfoWrapper<I> {
+            data: I,
         }
         log_request!("GET", path, resp);
         parse_response!(resp, 200 => {
-            Ok(resp.json::<NodeInfoWrapper>().await.unwrap().data)
+            Ok(resp.json::<NodeInfoWrapper<I>>().await?.data)
         })
     }
 
     /// GET /api/v1/info endpoint
     pub async fn get_info(&self) -> Result<NodeInfo> {
-        let mut url = self.get_node()?;
         let path = "api/v1/info";
-        url.set_path(path);
-        let resp = self
-            .client
-            .get(url)
-            .timeout(self.get_timeout(Api::GetInfo))
-            .send()
-            .await?;
+        let resp = self.get_request(Api::GetInfo, path, None).await?;
 
         #[derive(Debug, Serialize, Deserialize)]
         struct NodeInfoWrapper {
@@ -514,19 +1088,70 @@ impl Client {
 
     /// GET /api/v1/peers endpoint
     pub async fn get_peers(&self) -> Result<Vec<PeerDto>> {
-        let mut url = self.get_node()?;
         let path = "api/v1/peers";
-        url.set_path(path);
+        let resp = self.get_request(Api::GetPeers, path, None).await?;
+
+        #[derive(Debug, Serialize, Deserialize)]
+        struct PeerWrapper {
+            data: Vec<PeerDto>,
+        }
+        log_request!("GET", path, resp);
+        parse_response!(resp, 200 => {
+            Ok(resp.json::<PeerWrapper>().await?.data)
+        })
+    }
+
+    /// Sends a single request built by `request` to the given node, with its credentials but without retrying on
+    /// other nodes, for the APIs that manage a specific node.
+    async fn send_node_request<F>(&self, node: &str, path: &str, request: F) -> Result<Response>
+    where
+        F: Fn(Url) -> RequestBuilder,
+    {
+        let node = Url::parse(node).map_err(|_| Error::UrlError)?;
+        Ok(self.send_request_to_node(&node, path, None, &request).await?)
+    }
+
+    /// POST /api/v1/peers endpoint
+    /// Adds a peer to the given node. The request is only sent once to this node, it isn't retried on other nodes.
+    /// The route is protected, so the node needs credentials, see `ClientBuilder::with_node_auth`.
+    pub async fn add_peer(&self, node: &str, multi_address: &str, alias: Option<&str>) -> Result<PeerDto> {
+        let path = "api/v1/peers";
+        let timeout = self.get_timeout(Api::AddPeer);
+        #[derive(Debug, Serialize)]
+        struct AddPeerRequest<'a> {
+            #[serde(rename = "multiAddress")]
+            multi_address: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            alias: Option<&'a str>,
+        }
+        let body = AddPeerRequest { multi_address, alias };
         let resp = self
-            .client
-            .get(url)
-            .timeout(self.get_timeout(Api::GetPeers))
-            .send()
+            .send_node_request(node, path, |url| self.client.post(url).timeout(timeout).json(&body))
             .await?;
 
         #[derive(Debug, Serialize, Deserialize)]
         struct PeerWrapper {
-            data: Vec<PeerDto>,
+            data: PeerDto,
+        }
+        log_request!("POST", path, resp);
+        parse_response!(resp, 200..=201 => {
+            Ok(resp.json::<PeerWrapper>().await?.data)
+        })
+    }
+
+    /// GET /api/v1/peers/{peerId} endpoint
+    /// Gets a peer of the given node.
+    /// The route is protected, so the node needs credentials, see `ClientBuilder::with_node_auth`.
+    pub async fn get_peer(&self, node: &str, peer_id: &str) -> Result<PeerDto> {
+        let path = &format!("api/v1/peers/{}", peer_id);
+        let timeout = self.get_timeout(Api::GetPeer);
+        let resp = self
+            .send_node_request(node, path, |url| self.client.get(url).timeout(timeout))
+            .await?;
+
+        #[derive(Debug, Serialize, Deserialize)]
+        struct PeerWrapper {
+            data: PeerDto,
         }
         log_request!("GET", path, resp);
         parse_response!(resp, 200 => {
@@ -534,17 +1159,27 @@ impl Client {
         })
     }
 
+    /// DELETE /api/v1/peers/{peerId} endpoint
+    /// Removes a peer from the given node. The request is only sent once to this node, it isn't retried on other
+    /// nodes.
+    /// The route is protected, so the node needs credentials, see `ClientBuilder::with_node_auth`.
+    pub async fn remove_peer(&self, node: &str, peer_id: &str) -> Result<()> {
+        let path = &format!("api/v1/peers/{}", peer_id);
+        let timeout = self.get_timeout(Api::RemovePeer);
+        let resp = self
+            .send_node_request(node, path, |url| self.client.delete(url).timeout(timeout))
+            .await?;
+
+        log_request!("DELETE", path, resp);
+        parse_response!(resp, 200..=204 => {
+            Ok(())
+        })
+    }
+
     /// GET /api/v1/tips endpoint
     pub async fn get_tips(&self) -> Result<Vec<MessageId>> {
-        let mut url = self.get_node()?;
         let path = "api/v1/tips";
-        url.set_path(path);
-        let resp = self
-            .client
-            .get(url)
-            .timeout(self.get_timeout(Api::GetTips))
-            .send()
-            .await?;
+        let resp = self.get_request(Api::GetTips, path, None).await?;
 
         #[derive(Debug, Serialize, Deserialize)]
         struct TipsWrapper {
@@ -564,24 +1199,48 @@ impl Client {
     }
 
     /// POST /api/v1/messages endpoint
+    /// With remote PoW, the message is only sent to nodes that support it, preferring the primary PoW node.
     pub async fn post_message(&self, message: &Message) -> Result<MessageId> {
-        let mut url = self.get_node()?;
+        let message = MessageDto::try_from(message).expect("Can't convert message into json");
+        self.send_message(|request| {
+            request
+                .header("content-type", "application/json; charset=UTF-8")
+                .json(&message)
+        })
+        .await
+    }
+
+    /// POST /api/v1/messages endpoint
+    /// Sends the packed bytes of the message instead of its JSON representation.
+    /// With remote PoW, the message is only sent to nodes that support it, preferring the primary PoW node.
+    pub async fn post_message_raw(&self, message: &Message) -> Result<MessageId> {
+        let message = message.pack_new();
+        self.send_message(|request| {
+            request
+                .header("content-type", "application/octet-stream")
+                .body(message.clone())
+        })
+        .await
+    }
+
+    /// Sends a message with the body set by `body` to the POST /api/v1/messages endpoint.
+    async fn send_message<F>(&self, body: F) -> Result<MessageId>
+    where
+        F: Fn(RequestBuilder) -> RequestBuilder,
+    {
         let path = "api/v1/messages";
-        url.set_path(path);
 
-        let timeout = if self.get_local_pow() {
+        let local_pow = self.get_local_pow();
+        let timeout = if local_pow {
             self.get_timeout(Api::PostMessage)
         } else {
             self.get_timeout(Api::PostMessageWithRemotePow)
         };
-        let message = MessageDto::try_from(message).expect("Can't convert message into json");
+        let request = |url| body(self.client.post(url).timeout(timeout));
+        // A message is only sent again if no node got it, otherwise it could be attached twice, with different parents
+        // and nonce on remote PoW
         let resp = self
-            .client
-            .post(url)
-            .timeout(timeout)
-            .header("content-type", "application/json; charset=UTF-8")
-            .json(&message)
-            .send()
+            .send_request_to_pool(!local_pow, false, path, None, request)
             .await?;
         #[derive(Debug, Serialize, Deserialize)]
         struct MessageIdResponseWrapper {
@@ -608,63 +1267,68 @@ impl Client {
 
     /// GET /api/v1/outputs/{outputId} endpoint
     /// Find an output by its transaction_id and corresponding output_index.
+    /// If the quorum is enabled, the output is requested from multiple nodes.
+    /// If the cache is enabled, spent outputs are cached.
     pub async fn get_output(&self, output_id: &UTXOInput) -> Result<OutputResponse> {
-        let mut url = self.get_node()?;
         let path = &format!(
             "api/v1/outputs/{}{}",
             output_id.output_id().transaction_id().to_string(),
             hex::encode(output_id.output_id().index().to_le_bytes())
         );
-        url.set_path(path);
-        let resp = self
-            .client
-            .get(url)
-            .timeout(self.get_timeout(Api::GetOutput))
-            .send()
-            .await?;
+        if let Some(output) = self.get_cached(path).await {
+            return Ok(output);
+        }
+        let output: OutputResponse = if self.quorum.is_some() {
+            self.get_quorum_request(Api::GetOutput, path, None).await?
+        } else {
+            let resp = self.get_request(Api::GetOutput, path, None).await?;
 
-        #[derive(Debug, Serialize, Deserialize)]
-        struct OutputWrapper {
-            data: OutputResponse,
+            #[derive(Debug, Serialize, Deserialize)]
+            struct OutputWrapper {
+                data: OutputResponse,
+            }
+            log_request!("GET", path, resp);
+            parse_response!(resp, 200 => {
+                let output_response = resp.json::<OutputWrapper>().await?;
+                Ok(output_response.data)
+            })?
+        };
+        // Unspent outputs can still be spent
+        if output.is_spent {
+            self.cache_response(path, &output).await;
         }
-        log_request!("GET", path, resp);
-        parse_response!(resp, 200 => {
-            let output_response = resp.json::<OutputWrapper>().await?;
-            Ok(output_response.data)
-        })
+        Ok(output)
     }
 
     /// Find all outputs based on the requests criteria. This method will try to query multiple nodes if
     /// the request amount exceeds individual node limit.
+    /// If the quorum is enabled, the address outputs and the outputs are requested from multiple nodes.
     pub async fn find_outputs(
         &self,
         outputs: &[UTXOInput],
         addresses: &[Bech32Address],
     ) -> Result<Vec<OutputResponse>> {
-        let mut output_metadata = Vec::<OutputResponse>::new();
-        // Use a `HashSet` to prevent duplicate output.
-        let mut output_to_query = HashSet::<UTXOInput>::new();
-
-        // Collect the `UTXOInput` in the HashSet.
-        for output in outputs {
-            output_to_query.insert(output.to_owned());
-        }
+        // Use `get_address()` API to get the address outputs first.
+        let address_outputs: Vec<Box<[UTXOInput]>> = stream::iter(addresses)
+            .map(|address| self.get_address().outputs(address))
+            .buffered(self.max_parallel_requests)
+            .try_collect()
+            .await?;
 
-        // Use `get_address()` API to get the address outputs first,
-        // then collect the `UTXOInput` in the HashSet.
-        for address in addresses {
-            let address_outputs = self.get_address().outputs(&address).await?;
-            for output in address_outputs.iter() {
-                output_to_query.insert(output.to_owned());
-            }
-        }
+        // Prevent duplicate outputs, the given outputs come first and then the address outputs.
+        let output_to_query = dedup(
+            outputs
+                .iter()
+                .chain(address_outputs.iter().flat_map(|outputs| outputs.iter()))
+                .cloned(),
+        );
 
         // Use `get_output` API to get the `OutputMetadata`.
-        for output in output_to_query {
-            let meta_data = self.get_output(&output).await?;
-            output_metadata.push(meta_data);
-        }
-        Ok(output_metadata)
+        stream::iter(&output_to_query)
+            .map(|output| self.get_output(output))
+            .buffered(self.max_parallel_requests)
+            .try_collect()
+            .await
     }
 
     /// GET /api/v1/addresses/{address} endpoint
@@ -674,45 +1338,88 @@ impl Client {
 
     /// GET /api/v1/milestones/{index} endpoint
     /// Get the milestone by the given index.
+    /// If the cache is enabled, the milestone is cached.
     pub async fn get_milestone(&self, index: u32) -> Result<MilestoneResponse> {
-        let mut url = self.get_node()?;
         let path = &format!("api/v1/milestones/{}", index);
-        url.set_path(path);
-        let resp = self
-            .client
-            .get(url)
-            .timeout(self.get_timeout(Api::GetMilestone))
-            .send()
-            .await?;
+        let milestone = match self.get_cached::<MilestoneResponseDto>(path).await {
+            Some(milestone) => milestone,
+            None => {
+                let resp = self.get_request(Api::GetMilestone, path, None).await?;
+                #[derive(Debug, Serialize, Deserialize)]
+                struct MilestoneWrapper {
+                    data: MilestoneResponseDto,
+                }
+                log_request!("GET", path, resp);
+                let milestone = parse_response!(resp, 200 => {
+                    Ok(resp.json::<MilestoneWrapper>().await?.data)
+                })?;
+                self.cache_response(path, &milestone).await;
+                milestone
+            }
+        };
+        let mut message_id = [0u8; 32];
+        hex::decode_to_slice(milestone.message_id, &mut message_id)?;
+        Ok(MilestoneResponse {
+            index: milestone.milestone_index,
+            message_id: MessageId::new(message_id),
+            timestamp: milestone.timestamp,
+        })
+    }
+
+    /// GET /api/v1/transactions/{transactionId}/included-message endpoint
+    /// Get the message that includes the transaction with the given id in the ledger.
+    pub async fn get_included_message(&self, transaction_id: &TransactionId) -> Result<Message> {
+        let path = &format!("api/v1/transactions/{}/included-message", transaction_id);
+        let resp = self.get_request(Api::GetIncludedMessage, path, None).await?;
+
         #[derive(Debug, Serialize, Deserialize)]
-        struct MilestoneWrapper {
-            data: MilestoneResponseDto,
+        struct MessageWrapper {
+            data: MessageDto,
         }
         log_request!("GET", path, resp);
         parse_response!(resp, 200 => {
-            let milestone = resp.json::<MilestoneWrapper>().await?.data;
-            let mut message_id = [0u8; 32];
-            hex::decode_to_slice(milestone.message_id, &mut message_id)?;
-            Ok(MilestoneResponse {
-                index: milestone.milestone_index,
-                message_id: MessageId::new(message_id),
-                timestamp: milestone.timestamp,
-            })
+            let message = resp.json::<MessageWrapper>().await?.data;
+            Message::try_from(&message).map_err(|error| Error::InvalidMessageDto(error.to_string()))
         })
     }
 
+    /// Gets the status of the transaction with the given id, from the ledger inclusion state of the message that
+    /// includes it.
+    pub async fn get_transaction_status(&self, transaction_id: &TransactionId) -> Result<TransactionStatus> {
+        let message_id = match self.get_included_message(transaction_id).await {
+            Ok(message) => message.id().0,
+            Err(Error::ResponseError(404, _)) => return Ok(TransactionStatus::Unknown),
+            Err(error) => return Err(error),
+        };
+        let metadata = self.get_message().metadata(&message_id).await?;
+        Ok(TransactionStatus::from_inclusion_state(
+            message_id,
+            metadata.ledger_inclusion_state.as_ref(),
+            metadata.referenced_by_milestone_index,
+            metadata.conflict_reason,
+        ))
+    }
+
+    /// Walks the milestones from `start` to `end`, both included, see `MilestoneWalker`.
+    pub fn walk_milestones(&self, start: u32, end: u32) -> MilestoneWalker<'_> {
+        MilestoneWalker::new(self, start, end)
+    }
+
+    /// Gets the milestone payload of the milestone message with the given id, like the `message_id` of a
+    /// `MilestoneResponse`.
+    pub async fn get_milestone_payload(&self, message_id: &MessageId) -> Result<MilestonePayload> {
+        let message = self.get_message().data(message_id).await?;
+        match message.payload() {
+            Some(Payload::Milestone(milestone)) => Ok((**milestone).clone()),
+            _ => Err(Error::NotAMilestone(message_id.to_string())),
+        }
+    }
+
     /// GET /api/v1/milestones/{index}/utxo-changes endpoint
     /// Get the milestone by the given index.
     pub async fn get_milestone_utxo_changes(&self, index: u32) -> Result<MilestoneUTXOChanges> {
-        let mut url = self.get_node()?;
         let path = &format!("api/v1/milestones/{}/utxo-changes", index);
-        url.set_path(path);
-        let resp = self
-            .client
-            .get(url)
-            .timeout(self.get_timeout(Api::GetMilestone))
-            .send()
-            .await?;
+        let resp = self.get_request(Api::GetMilestoneUtxoChanges, path, None).await?;
         #[derive(Debug, Serialize, Deserialize)]
         struct MilestoneUTXOChangesWrapper {
             data: MilestoneUTXOChanges,
@@ -724,6 +1431,51 @@ impl Client {
         })
     }
 
+    /// GET /api/v1/receipts endpoint
+    /// Get all migration receipts.
+    pub async fn get_receipts(&self) -> Result<Vec<ReceiptDto>> {
+        let path = "api/v1/receipts";
+        self.request_receipts(Api::GetReceipts, path).await
+    }
+
+    /// GET /api/v1/receipts/{migratedAt} endpoint
+    /// Get the migration receipts included in the milestone with the given index.
+    pub async fn get_receipts_migrated_at(&self, milestone_index: u32) -> Result<Vec<ReceiptDto>> {
+        let path = &format!("api/v1/receipts/{}", milestone_index);
+        self.request_receipts(Api::GetReceiptsMigratedAt, path).await
+    }
+
+    async fn request_receipts(&self, api: Api, path: &str) -> Result<Vec<ReceiptDto>> {
+        let resp = self.get_request(api, path, None).await?;
+        #[derive(Debug, Serialize, Deserialize)]
+        struct ReceiptsResponse {
+            receipts: Vec<ReceiptDto>,
+        }
+        #[derive(Debug, Serialize, Deserialize)]
+        struct ReceiptsWrapper {
+            data: ReceiptsResponse,
+        }
+        log_request!("GET", path, resp);
+        parse_response!(resp, 200 => {
+            Ok(resp.json::<ReceiptsWrapper>().await?.data.receipts)
+        })
+    }
+
+    /// GET /api/v1/treasury endpoint
+    /// Get the current treasury output.
+    pub async fn get_treasury(&self) -> Result<TreasuryResponse> {
+        let path = "api/v1/treasury";
+        let resp = self.get_request(Api::GetTreasury, path, None).await?;
+        #[derive(Debug, Serialize, Deserialize)]
+        struct TreasuryWrapper {
+            data: TreasuryResponse,
+        }
+        log_request!("GET", path, resp);
+        parse_response!(resp, 200 => {
+            Ok(resp.json::<TreasuryWrapper>().await?.data)
+        })
+    }
+
     /// Reattaches messages for provided message id. Messages can be reattached only if they are valid and haven't been
     /// confirmed for a while.
     pub async fn reattach(&self, message_id: &MessageId) -> Result<(MessageId, Message)> {
@@ -804,36 +1556,60 @@ impl Client {
     }
 
     /// Find all messages by provided message IDs and/or indexation_keys.
+    /// The messages that couldn't be retrieved are returned as errors instead of failing the whole lookup.
     pub async fn find_messages<I: AsRef<[u8]>>(
         &self,
         indexation_keys: &[I],
         message_ids: &[MessageId],
-    ) -> Result<Vec<Message>> {
-        let mut messages = Vec::new();
-
-        // Use a `HashSet` to prevent duplicate message_ids.
-        let mut message_ids_to_query = HashSet::<MessageId>::new();
-
-        // Collect the `MessageId` in the HashSet.
-        for message_id in message_ids {
-            message_ids_to_query.insert(message_id.to_owned());
-        }
-
-        // Use `get_message().index()` API to get the message ID first,
-        // then collect the `MessageId` in the HashSet.
-        for index in indexation_keys {
-            let message_ids = self.get_message().index(index).await?;
-            for message_id in message_ids.iter() {
-                message_ids_to_query.insert(message_id.to_owned());
+    ) -> Result<FoundMessages> {
+        let mut found_messages = FoundMessages::default();
+
+        // Use `get_message().index()` API to get the message ID first.
+        let index_results: Vec<Result<Box<[MessageId]>>> = stream::iter(indexation_keys)
+            .map(|index| self.get_message().index(index))
+            .buffered(self.max_parallel_requests)
+            .collect()
+            .await;
+        let mut index_message_ids = Vec::new();
+        for (index, result) in indexation_keys.iter().zip(index_results) {
+            match result {
+                Ok(message_ids) => index_message_ids.push(message_ids),
+                Err(error) => found_messages
+                    .index_errors
+                    .push((index.as_ref().to_vec(), MessageLookupError::from_error(error, false))),
             }
         }
 
+        // Prevent duplicate message_ids, the given message_ids come first and then the indexed ones.
+        let message_ids_to_query = dedup(
+            message_ids
+                .iter()
+                .chain(index_message_ids.iter().flat_map(|message_ids| message_ids.iter()))
+                .cloned(),
+        );
+
+        let indexed_message_ids: HashSet<&MessageId> = index_message_ids
+            .iter()
+            .flat_map(|message_ids| message_ids.iter())
+            .collect();
+
         // Use `get_message().data()` API to get the `Message`.
-        for message_id in message_ids_to_query {
-            let message = self.get_message().data(&message_id).await.unwrap();
-            messages.push(message);
+        let results: Vec<Result<Message>> = stream::iter(&message_ids_to_query)
+            .map(|message_id| self.get_message().data(message_id))
+            .buffered(self.max_parallel_requests)
+            .collect()
+            .await;
+
+        for (message_id, result) in message_ids_to_query.iter().zip(results) {
+            match result {
+                Ok(message) => found_messages.messages.push(message),
+                Err(error) => found_messages.errors.push((
+                    *message_id,
+                    MessageLookupError::from_error(error, indexed_message_ids.contains(message_id)),
+                )),
+            }
         }
-        Ok(messages)
+        Ok(found_messages)
     }
 
     /// Return the balance for a provided seed and its wallet chain account index.
@@ -846,12 +1622,11 @@ impl Client {
     /// Return the balance in iota for the given addresses; No seed or security level needed to do this
     /// since we are only checking and already know the addresses.
     pub async fn get_address_balances(&self, addresses: &[Bech32Address]) -> Result<Vec<BalanceForAddressResponse>> {
-        let mut address_balance_pairs = Vec::new();
-        for address in addresses {
-            let balance_response = self.get_address().balance(&address).await?;
-            address_balance_pairs.push(balance_response);
-        }
-        Ok(address_balance_pairs)
+        stream::iter(addresses)
+            .map(|address| self.get_address().balance(address))
+            .buffered(self.max_parallel_requests)
+            .try_collect()
+            .await
     }
 
     /// Retries (promotes or reattaches) a message for provided message id. Message should only be
@@ -869,6 +1644,12 @@ impl Client {
     }
 }
 
+/// Removes the duplicates, keeping the first occurrence of each item in place.
+fn dedup<T: Clone + Eq + Hash>(items: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut seen = HashSet::new();
+    items.into_iter().filter(|item| seen.insert(item.clone())).collect()
+}
+
 /// Hash the network id str from the nodeinfo to an u64 for the messageBuilder
 pub fn hash_network(network_id: &str) -> u64 {
     let mut hasher = VarBlake2b::new(32).unwrap();
@@ -879,3 +1660,133 @@ pub fn hash_network(network_id: &str) -> u64 {
     });
     u64::from_le_bytes(result[0..8].try_into().unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_is_clone_send_and_sync() {
+        fn assert_send_sync<T: Clone + Send + Sync>() {}
+        assert_send_sync::<Client>();
+    }
+
+    #[test]
+    fn dedup_keeps_the_first_occurrences_in_order() {
+        assert_eq!(dedup(vec![3, 1, 3, 2, 1]), vec![3, 1, 2]);
+        assert_eq!(dedup(Vec::<u8>::new()), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn conflict_reasons_are_mapped() {
+        let reasons = [
+            (1, ConflictReason::InputUtxoAlreadySpent),
+            (2, ConflictReason::InputUtxoAlreadySpentInThisMilestone),
+            (3, ConflictReason::InputUtxoNotFound),
+            (4, ConflictReason::InputOutputSumMismatch),
+            (5, ConflictReason::InvalidSignature),
+            (6, ConflictReason::InvalidDustAllowance),
+            (255, ConflictReason::SemanticValidationFailed),
+            (7, ConflictReason::Other(7)),
+        ];
+        for (code, reason) in reasons.iter() {
+            assert_eq!(ConflictReason::from(*code), *reason);
+        }
+    }
+
+    #[test]
+    fn transaction_status_follows_the_ledger_inclusion_state() {
+        let message_id = MessageId::new([1; 32]);
+        assert_eq!(
+            TransactionStatus::from_inclusion_state(message_id, None, None, None),
+            TransactionStatus::Pending(message_id)
+        );
+        assert_eq!(
+            TransactionStatus::from_inclusion_state(
+                message_id,
+                Some(&LedgerInclusionStateDto::Included),
+                Some(42),
+                None
+            ),
+            TransactionStatus::Included {
+                message_id,
+                milestone_index: Some(42)
+            }
+        );
+        assert_eq!(
+            TransactionStatus::from_inclusion_state(
+                message_id,
+                Some(&LedgerInclusionStateDto::Conflicting),
+                Some(42),
+                Some(6)
+            ),
+            TransactionStatus::Conflicting {
+                message_id,
+                reason: Some(ConflictReason::InvalidDustAllowance)
+            }
+        );
+        assert_eq!(
+            TransactionStatus::from_inclusion_state(
+                message_id,
+                Some(&LedgerInclusionStateDto::NoTransaction),
+                Some(42),
+                None
+            ),
+            TransactionStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn node_info_snapshot_is_deserialized() {
+        let info: NodeInfoSnapshot = serde_json::from_str(
+            r#"{"name":"HORNET","version":"0.6.0-alpha","isHealthy":true,"networkId":"testnet7","bech32HRP":"atoi","minPowScore":4000,"latestMilestoneIndex":137,"solidMilestoneIndex":137,"pruningIndex":0,"features":["PoW"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            info,
+            NodeInfoSnapshot {
+                name: "HORNET".into(),
+                version: "0.6.0-alpha".into(),
+                is_healthy: true,
+                network_id: "testnet7".into(),
+                bech32_hrp: "atoi".into(),
+                min_pow_score: 4000.0,
+                latest_milestone_index: 137,
+                confirmed_milestone_index: 137,
+                pruning_index: 0,
+                features: vec!["PoW".into()],
+                messages_per_second: None,
+            }
+        );
+
+        // Newer nodes renamed some fields and report the message rate
+        let info: NodeInfoSnapshot = serde_json::from_str(
+            r#"{"name":"HORNET","version":"1.0.0","isHealthy":true,"networkId":"chrysalis-mainnet","bech32HRP":"iota","minPoWScore":4000,"messagesPerSecond":9.5,"referencedMessagesPerSecond":9.2,"referencedRate":96.8,"latestMilestoneTimestamp":1619179582,"latestMilestoneIndex":1000,"confirmedMilestoneIndex":999,"pruningIndex":0,"features":["PoW"]}"#,
+        )
+        .unwrap();
+        assert_eq!(info.min_pow_score, 4000.0);
+        assert_eq!(info.confirmed_milestone_index, 999);
+        assert_eq!(info.messages_per_second, Some(9.5));
+    }
+
+    #[test]
+    fn message_lookup_errors_are_classified() {
+        let not_found = || Error::ResponseError(404, "message not found".into());
+        assert!(matches!(
+            MessageLookupError::from_error(not_found(), false),
+            MessageLookupError::NotFound
+        ));
+        assert!(matches!(
+            MessageLookupError::from_error(not_found(), true),
+            MessageLookupError::Pruned
+        ));
+        assert!(matches!(
+            MessageLookupError::from_error(Error::InvalidMessageDto("invalid payload".into()), true),
+            MessageLookupError::Conversion(error) if error == "invalid payload"
+        ));
+        assert!(matches!(
+            MessageLookupError::from_error(Error::ResponseError(500, "internal error".into()), true),
+            MessageLookupError::Request(Error::ResponseError(500, _))
+        ));
+    }
+}
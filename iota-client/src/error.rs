@@ -42,6 +42,9 @@ pub enum Error {
     /// Error on Url type conversion
     #[error("Failed to parse node_pool_urls")]
     NodePoolUrlsError,
+    /// Invalid HTTP header name or value
+    #[error("Invalid HTTP header: {0}")]
+    InvalidHeader(String),
     /// Errors from reqwest api call
     #[error("{0}")]
     ReqwestError(#[from] reqwest::Error),
@@ -94,6 +97,22 @@ pub enum Error {
     /// Slip10 error
     #[error("{0}")]
     Slip10Error(slip10::Error),
+    /// Not enough synced nodes to reach the quorum
+    #[error("Not enough synced nodes for the quorum: {0} available, {1} required")]
+    QuorumPoolSizeError(usize, usize),
+    /// The quorum threshold wasn't reached, contains the amount of agreeing and queried nodes and the nodes that
+    /// returned a different or no response
+    #[error("Quorum threshold not reached: {0} of {1} nodes agreed, disagreeing nodes: {2:?}")]
+    QuorumThresholdError(usize, usize, Vec<String>),
+    /// A message returned by a node couldn't be converted
+    #[error("Invalid message: {0}")]
+    InvalidMessageDto(String),
+    /// The node truncated the outputs of an address
+    #[error("The node truncated the outputs of the address at {0} results")]
+    OutputsTruncated(usize),
+    /// The message doesn't contain a milestone payload
+    #[error("Message ID `{0}` doesn't contain a milestone payload")]
+    NotAMilestone(String),
     /// Invalid amount of parents
     #[error("Invalid amount of parents, length must be in 1..=8")]
     InvalidParentsAmount,
@@ -11,9 +11,11 @@ extern crate serde;
 
 pub mod api;
 pub mod builder;
+mod cache;
 pub mod client;
 pub mod error;
 pub mod node;
+pub mod node_manager;
 pub mod seed;
 #[cfg(feature = "storage")]
 #[cfg_attr(docsrs, doc(cfg(feature = "storage")))]
@@ -23,14 +25,15 @@ pub use bee_message;
 pub use bee_rest_api::{
     self,
     handlers::{balance_ed25519::BalanceForAddressResponse, output::OutputResponse},
-    types::{AddressDto, OutputDto},
+    types::{AddressDto, OutputDto, ReceiptDto},
 };
 // pub use bee_signing_ext::{self, binary::BIP32Path,};
-pub use builder::ClientBuilder;
+pub use builder::{ClientBuilder, HttpOptions};
 pub use client::*;
 pub use error::*;
 #[cfg(feature = "mqtt")]
 pub use node::Topic;
+pub use node_manager::{NodeAuth, NodeEvent, NodeSelectionStrategy, NodeStats, NodeStatus, RequestLimits, RetryPolicy};
 pub use reqwest::Url;
 pub use seed::*;
 #[cfg(feature = "storage")]
@@ -45,11 +48,6 @@ mod async_runtime {
 
     static RUNTIME: OnceCell<Mutex<Runtime>> = OnceCell::new();
 
-    pub(crate) fn block_on<C: futures::Future>(cb: C) -> C::Output {
-        let runtime = RUNTIME.get_or_init(|| Mutex::new(Runtime::new().unwrap()));
-        runtime.lock().unwrap().block_on(cb)
-    }
-
     pub(crate) fn spawn<F>(future: F)
     where
         F: futures::Future + Send + 'static,
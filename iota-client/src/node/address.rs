@@ -1,9 +1,9 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{log_request, parse_response, Client, Error, Result};
+use crate::{log_request, parse_response, Api, Client, Error, Result};
 
-use bee_message::prelude::{Bech32Address, TransactionId, UTXOInput};
+use bee_message::prelude::{Bech32Address, Ed25519Address, TransactionId, UTXOInput};
 
 use bee_rest_api::handlers::{balance_ed25519::BalanceForAddressResponse, outputs_ed25519::OutputsForAddressResponse};
 
@@ -25,11 +25,23 @@ impl<'a> GetAddressBuilder<'a> {
     /// Consume the builder and get the balance of a given Bech32 encoded address.
     /// If count equals maxResults, then there might be more outputs available but those were skipped for performance
     /// reasons. User should sweep the address to reduce the amount of outputs.
+    /// If the quorum is enabled, the balance is requested from multiple nodes.
     pub async fn balance(self, address: &Bech32Address) -> Result<BalanceForAddressResponse> {
-        let mut url = self.client.get_node()?;
-        let path = &format!("api/v1/addresses/{}", address);
-        url.set_path(path);
-        let resp = reqwest::get(url).await?;
+        self.balance_at(&format!("api/v1/addresses/{}", address)).await
+    }
+
+    /// Consume the builder and get the balance of a given Ed25519 address, without knowing the Bech32 HRP of the
+    /// network.
+    /// If the quorum is enabled, the balance is requested from multiple nodes.
+    pub async fn balance_ed25519(self, address: &Ed25519Address) -> Result<BalanceForAddressResponse> {
+        self.balance_at(&format!("api/v1/addresses/ed25519/{}", address)).await
+    }
+
+    async fn balance_at(self, path: &str) -> Result<BalanceForAddressResponse> {
+        if self.client.quorum.is_some() {
+            return self.client.get_quorum_request(Api::GetAddressBalance, path, None).await;
+        }
+        let resp = self.client.get_request(Api::GetAddressBalance, path, None).await?;
 
         #[derive(Debug, Serialize, Deserialize)]
         struct BalanceWrapper {
@@ -44,32 +56,173 @@ impl<'a> GetAddressBuilder<'a> {
 
     /// Consume the builder and get all outputs that use a given address.
     /// If count equals maxResults, then there might be more outputs available but those were skipped for performance
-    /// reasons. User should sweep the address to reduce the amount of outputs.
+    /// reasons. User should sweep the address to reduce the amount of outputs. Use `outputs_query` to detect it.
+    /// If the quorum is enabled, the outputs are requested from multiple nodes.
     pub async fn outputs(self, address: &Bech32Address) -> Result<Box<[UTXOInput]>> {
-        let mut url = self.client.get_node()?;
-        let path = &format!("api/v1/addresses/{}/outputs", address);
-        url.set_path(path);
-        let resp = reqwest::get(url).await?;
+        Ok(self.outputs_query(address).finish().await?.output_ids)
+    }
 
-        #[derive(Debug, Serialize, Deserialize)]
-        struct OutputWrapper {
-            data: OutputsForAddressResponse,
+    /// Consume the builder and get all outputs that use a given Ed25519 address, without knowing the Bech32 HRP of
+    /// the network.
+    /// If the quorum is enabled, the outputs are requested from multiple nodes.
+    pub async fn outputs_ed25519(self, address: &Ed25519Address) -> Result<Box<[UTXOInput]>> {
+        Ok(self.outputs_ed25519_query(address).finish().await?.output_ids)
+    }
+
+    /// Consume the builder and create a query of the outputs of a given address, that can include spent outputs,
+    /// filter by output type and state whether the node truncated the outputs.
+    pub fn outputs_query(self, address: &Bech32Address) -> GetAddressOutputsBuilder<'a> {
+        GetAddressOutputsBuilder::new(self.client, format!("api/v1/addresses/{}/outputs", address))
+    }
+
+    /// Consume the builder and create a query of the outputs of a given Ed25519 address, see `outputs_query`.
+    pub fn outputs_ed25519_query(self, address: &Ed25519Address) -> GetAddressOutputsBuilder<'a> {
+        GetAddressOutputsBuilder::new(self.client, format!("api/v1/addresses/ed25519/{}/outputs", address))
+    }
+}
+
+/// Outputs of an address returned by `GetAddressOutputsBuilder`.
+#[derive(Debug, Clone)]
+pub struct AddressOutputs {
+    /// Outputs of the address.
+    pub output_ids: Box<[UTXOInput]>,
+    /// Maximum amount of outputs the node returns.
+    pub max_results: usize,
+    /// Whether the outputs are complete. If not, the node skipped outputs for performance reasons, the address should
+    /// be swept to reduce the amount of outputs.
+    pub complete: bool,
+}
+
+/// Builder of GET /api/v1/addresses/{address}/outputs endpoint with query parameters
+pub struct GetAddressOutputsBuilder<'a> {
+    client: &'a Client,
+    path: String,
+    include_spent: bool,
+    output_type: Option<u8>,
+    require_complete: bool,
+}
+
+impl<'a> GetAddressOutputsBuilder<'a> {
+    fn new(client: &'a Client, path: String) -> Self {
+        Self {
+            client,
+            path,
+            include_spent: false,
+            output_type: None,
+            require_complete: false,
         }
-        log_request!("GET", path, resp);
-        parse_response!(resp, 200 => {
-            let r = resp.json::<OutputWrapper>().await?.data;
-            r.output_ids.iter()
-                .map(|s| {
-                    let mut transaction_id = [0u8; 32];
-                    hex::decode_to_slice(&s[..64], &mut transaction_id)?;
-                    let index = u16::from_le_bytes(
-                        hex::decode(&s[64..]).map_err(|_| Error::InvalidParameter("index".to_string()))?[..]
-                            .try_into()
-                            .map_err(|_| Error::InvalidParameter("index".to_string()))?,
-                    );
-                    Ok(UTXOInput::new(TransactionId::new(transaction_id), index)?)
-                })
-                .collect::<Result<Box<[UTXOInput]>>>()
+    }
+
+    /// Sets whether spent outputs are included, only unspent outputs are returned by default.
+    pub fn with_include_spent(mut self, include_spent: bool) -> Self {
+        self.include_spent = include_spent;
+        self
+    }
+
+    /// Only returns the outputs of the given type, like `0` for `SignatureLockedSingle` outputs or `1` for
+    /// `SignatureLockedDustAllowance` outputs.
+    pub fn with_output_type(mut self, output_type: u8) -> Self {
+        self.output_type = Some(output_type);
+        self
+    }
+
+    /// Sets whether an `Error::OutputsTruncated` is returned instead of outputs that aren't complete.
+    pub fn with_require_complete(mut self, require_complete: bool) -> Self {
+        self.require_complete = require_complete;
+        self
+    }
+
+    /// Consume the builder and get the outputs.
+    /// If the quorum is enabled, the outputs are requested from multiple nodes.
+    pub async fn finish(self) -> Result<AddressOutputs> {
+        let path = &self.path;
+        let query = outputs_query(self.include_spent, self.output_type);
+        let query = query.as_deref();
+
+        let outputs: OutputsForAddressResponse = if self.client.quorum.is_some() {
+            self.client
+                .get_quorum_request(Api::GetAddressOutputs, path, query)
+                .await?
+        } else {
+            let resp = self.client.get_request(Api::GetAddressOutputs, path, query).await?;
+
+            #[derive(Debug, Serialize, Deserialize)]
+            struct OutputWrapper {
+                data: OutputsForAddressResponse,
+            }
+            log_request!("GET", path, resp);
+            parse_response!(resp, 200 => {
+                Ok(resp.json::<OutputWrapper>().await?.data)
+            })?
+        };
+
+        let output_ids = outputs
+            .output_ids
+            .iter()
+            .map(|s| {
+                let mut transaction_id = [0u8; 32];
+                hex::decode_to_slice(&s[..64], &mut transaction_id)?;
+                let index = u16::from_le_bytes(
+                    hex::decode(&s[64..]).map_err(|_| Error::InvalidParameter("index".to_string()))?[..]
+                        .try_into()
+                        .map_err(|_| Error::InvalidParameter("index".to_string()))?,
+                );
+                Ok(UTXOInput::new(TransactionId::new(transaction_id), index)?)
+            })
+            .collect::<Result<Box<[UTXOInput]>>>()?;
+
+        let complete = outputs_complete(outputs.count, outputs.max_results);
+        if self.require_complete && !complete {
+            return Err(Error::OutputsTruncated(outputs.max_results));
+        }
+        Ok(AddressOutputs {
+            output_ids,
+            max_results: outputs.max_results,
+            complete,
         })
     }
 }
+
+/// Query string of the address outputs endpoint, `None` if no parameter is set.
+fn outputs_query(include_spent: bool, output_type: Option<u8>) -> Option<String> {
+    let mut query = Vec::new();
+    if include_spent {
+        query.push("include-spent=true".to_string());
+    }
+    if let Some(output_type) = output_type {
+        query.push(format!("type={}", output_type));
+    }
+    if query.is_empty() {
+        None
+    } else {
+        Some(query.join("&"))
+    }
+}
+
+/// Whether the node returned all outputs of an address. The node stops at `max_results`, so there might be more.
+fn outputs_complete(count: usize, max_results: usize) -> bool {
+    count < max_results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outputs_query_string() {
+        assert_eq!(outputs_query(false, None), None);
+        assert_eq!(outputs_query(true, None).as_deref(), Some("include-spent=true"));
+        assert_eq!(outputs_query(false, Some(1)).as_deref(), Some("type=1"));
+        assert_eq!(
+            outputs_query(true, Some(0)).as_deref(),
+            Some("include-spent=true&type=0")
+        );
+    }
+
+    #[test]
+    fn outputs_are_complete_below_max_results() {
+        assert!(outputs_complete(0, 1000));
+        assert!(outputs_complete(999, 1000));
+        assert!(!outputs_complete(1000, 1000));
+    }
+}
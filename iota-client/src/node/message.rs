@@ -1,7 +1,7 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{log_request, parse_response, Client, Error, Result};
+use crate::{log_request, parse_response, Api, Client, Error, Result};
 use bee_message::{Message, MessageId};
 use bee_rest_api::{
     handlers::{
@@ -29,11 +29,12 @@ impl<'a> GetMessageBuilder<'a> {
     /// GET /api/v1/messages?index={Index} endpoint
     /// Consume the builder and search for messages matching the index
     pub async fn index<I: AsRef<[u8]>>(self, index: I) -> Result<Box<[MessageId]>> {
-        let mut url = self.client.get_node()?;
         let path = "api/v1/messages";
-        url.set_path(path);
-        url.set_query(Some(&format!("index={}", hex::encode(index))));
-        let resp = reqwest::get(url).await?;
+        let query = format!("index={}", hex::encode(index));
+        let resp = self
+            .client
+            .get_request(Api::GetMessagesByIndex, path, Some(&query))
+            .await?;
 
         #[derive(Debug, Serialize, Deserialize)]
         struct MessagesWrapper {
@@ -55,31 +56,34 @@ impl<'a> GetMessageBuilder<'a> {
 
     /// GET /api/v1/messages/{messageID} endpoint
     /// Consume the builder and find a message by its identifer. This method returns the given message object.
+    /// If the cache is enabled, the message is cached.
     pub async fn data(self, message_id: &MessageId) -> Result<Message> {
-        let mut url = self.client.get_node()?;
         let path = &format!("api/v1/messages/{}", message_id);
-        url.set_path(path);
-        let resp = reqwest::get(url).await?;
+        let message = match self.client.get_cached::<MessageDto>(path).await {
+            Some(message) => message,
+            None => {
+                let resp = self.client.get_request(Api::GetMessage, path, None).await?;
 
-        #[derive(Debug, Serialize, Deserialize)]
-        struct MessagesWrapper {
-            data: MessageDto,
-        }
-        log_request!("GET", path, resp);
-        parse_response!(resp, 200 => {
-            let meta = resp.json::<MessagesWrapper>().await?;
-            Ok(
-                Message::try_from(&meta.data).expect("Can't convert MessageDto to Message"))
-        })
+                #[derive(Debug, Serialize, Deserialize)]
+                struct MessagesWrapper {
+                    data: MessageDto,
+                }
+                log_request!("GET", path, resp);
+                let message = parse_response!(resp, 200 => {
+                    Ok(resp.json::<MessagesWrapper>().await?.data)
+                })?;
+                self.client.cache_response(path, &message).await;
+                message
+            }
+        };
+        Message::try_from(&message).map_err(|error| Error::InvalidMessageDto(error.to_string()))
     }
 
     /// GET /api/v1/messages/{messageID}/metadata endpoint
     /// Consume the builder and find a message by its identifer. This method returns the given message metadata.
     pub async fn metadata(self, message_id: &MessageId) -> Result<MessageMetadata> {
-        let mut url = self.client.get_node()?;
         let path = &format!("api/v1/messages/{}/metadata", message_id);
-        url.set_path(path);
-        let resp = reqwest::get(url).await?;
+        let resp = self.client.get_request(Api::GetMessageMetadata, path, None).await?;
         #[derive(Debug, Serialize, Deserialize)]
         struct MessagesWrapper {
             data: MessageMetadata,
@@ -91,26 +95,29 @@ impl<'a> GetMessageBuilder<'a> {
         })
     }
 
-    /// GET /api/v1/messages/{messageID}/children endpoint
-    /// Consume the builder and find a message by its identifer. This method returns the given message raw data.
-    pub async fn raw(self, message_id: &MessageId) -> Result<String> {
-        let mut url = self.client.get_node()?;
+    /// GET /api/v1/messages/{messageID}/raw endpoint
+    /// Consume the builder and find a message by its identifer. This method returns the given message raw data, the
+    /// packed bytes that can be unpacked into a `Message` with `Packable::unpack`.
+    /// If the cache is enabled, the raw message is cached hex encoded.
+    pub async fn raw(self, message_id: &MessageId) -> Result<Vec<u8>> {
         let path = &format!("api/v1/messages/{}/raw", message_id);
-        url.set_path(path);
-        let resp = reqwest::get(url).await?;
+        if let Some(Ok(raw)) = self.client.get_cached::<String>(path).await.map(hex::decode) {
+            return Ok(raw);
+        }
+        let resp = self.client.get_request(Api::GetMessageRaw, path, None).await?;
 
         log_request!("GET", path, resp);
-        parse_response!(resp, 200 => {
-            Ok(resp.text().await?)
-        })
+        let raw = parse_response!(resp, 200 => {
+            Ok(resp.bytes().await?.to_vec())
+        })?;
+        self.client.cache_response(path, &hex::encode(&raw)).await;
+        Ok(raw)
     }
 
     /// Consume the builder and returns the list of message IDs that reference a message by its identifier.
     pub async fn children(self, message_id: &MessageId) -> Result<Box<[MessageId]>> {
-        let mut url = self.client.get_node()?;
         let path = &format!("api/v1/messages/{}/children", message_id);
-        url.set_path(path);
-        let resp = reqwest::get(url).await?;
+        let resp = self.client.get_request(Api::GetMessageChildren, path, None).await?;
 
         #[derive(Debug, Serialize, Deserialize)]
         struct MessagesWrapper {
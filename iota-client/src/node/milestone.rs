@@ -0,0 +1,117 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Client, MilestoneResponse, Result};
+
+use bee_rest_api::handlers::milestone_utxo_changes::MilestoneUtxoChanges as MilestoneUTXOChanges;
+
+use futures::{stream, Stream, StreamExt};
+
+use std::ops::RangeInclusive;
+
+/// Milestone returned by the `MilestoneWalker`
+#[derive(Debug)]
+pub struct WalkedMilestone {
+    /// The milestone.
+    pub milestone: MilestoneResponse,
+    /// The UTXO changes of the milestone, if requested with `MilestoneWalker::with_utxo_changes`.
+    pub utxo_changes: Option<MilestoneUTXOChanges>,
+}
+
+/// Walker of a range of milestones
+pub struct MilestoneWalker<'a> {
+    client: &'a Client,
+    start: u32,
+    end: u32,
+    utxo_changes: bool,
+    max_parallel_requests: usize,
+}
+
+impl<'a> MilestoneWalker<'a> {
+    /// Create a walker of the milestones from `start` to `end`, both included.
+    pub fn new(client: &'a Client, start: u32, end: u32) -> Self {
+        Self {
+            client,
+            start,
+            end,
+            utxo_changes: false,
+            max_parallel_requests: client.max_parallel_requests,
+        }
+    }
+
+    /// Sets whether the UTXO changes of each milestone are requested too.
+    pub fn with_utxo_changes(mut self, utxo_changes: bool) -> Self {
+        self.utxo_changes = utxo_changes;
+        self
+    }
+
+    /// Sets the maximum of milestones requested concurrently. Default: the one of the client, see
+    /// `ClientBuilder::with_max_parallel_requests`.
+    pub fn with_max_parallel_requests(mut self, max_parallel_requests: usize) -> Self {
+        self.max_parallel_requests = max_parallel_requests.max(1);
+        self
+    }
+
+    /// Resumes an interrupted walk at the given index, usually the one after the last milestone received.
+    pub fn resume_from(mut self, index: u32) -> Self {
+        self.start = self.start.max(index);
+        self
+    }
+
+    /// Indexes of the milestones that are walked.
+    fn indexes(&self) -> RangeInclusive<u32> {
+        self.start..=self.end
+    }
+
+    /// Consume the walker and stream the milestones ordered by index.
+    /// A milestone that couldn't be retrieved is streamed as an error and the walk goes on with the next ones.
+    pub fn stream(self) -> impl Stream<Item = Result<WalkedMilestone>> + 'a {
+        let client = self.client;
+        let utxo_changes = self.utxo_changes;
+        stream::iter(self.indexes())
+            .map(move |index| async move {
+                let milestone = client.get_milestone(index).await?;
+                let utxo_changes = match utxo_changes {
+                    true => Some(client.get_milestone_utxo_changes(index).await?),
+                    false => None,
+                };
+                Ok(WalkedMilestone {
+                    milestone,
+                    utxo_changes,
+                })
+            })
+            .buffered(self.max_parallel_requests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn client() -> Client {
+        Client::builder()
+            .with_node("http://node:14265")
+            .unwrap()
+            .with_node_sync_disabled()
+            .finish()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn walker_covers_the_range() {
+        let client = client().await;
+        assert_eq!(client.walk_milestones(3, 7).indexes(), 3..=7);
+        assert_eq!(client.walk_milestones(5, 5).indexes().count(), 1);
+        assert_eq!(client.walk_milestones(7, 3).indexes().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn walker_resumes_inside_the_range() {
+        let client = client().await;
+        assert_eq!(client.walk_milestones(3, 7).resume_from(5).indexes(), 5..=7);
+        // Resuming before the start doesn't walk more milestones
+        assert_eq!(client.walk_milestones(3, 7).resume_from(1).indexes(), 3..=7);
+        assert_eq!(client.walk_milestones(3, 7).resume_from(8).indexes().count(), 0);
+    }
+}
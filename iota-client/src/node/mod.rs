@@ -5,10 +5,12 @@
 
 mod address;
 mod message;
+mod milestone;
 #[cfg(feature = "mqtt")]
 mod mqtt;
 
 pub use address::*;
 pub use message::*;
+pub use milestone::*;
 #[cfg(feature = "mqtt")]
 pub use mqtt::*;
@@ -3,6 +3,7 @@
 
 use crate::{
     client::{Client, TopicEvent, TopicHandlerMap},
+    node_manager::{join_path, NodeAuth},
     Result,
 };
 use paho_mqtt::{
@@ -10,10 +11,14 @@ use paho_mqtt::{
     MQTT_VERSION_3_1_1,
 };
 use regex::Regex;
+use reqwest::Url;
 use tokio::sync::RwLock;
 
 use std::{convert::TryFrom, sync::Arc, time::Duration};
 
+/// User name of the MQTT connection when authenticating with a JWT, MQTT 3.1.1 doesn't allow a password without it.
+const JWT_USER_NAME: &str = "jwt";
+
 macro_rules! lazy_static {
     ($init:expr => $type:ty) => {{
         static mut VALUE: Option<$type> = None;
@@ -63,44 +68,66 @@ impl Topic {
     }
 }
 
-fn get_mqtt_client(client: &mut Client) -> Result<&MqttClient> {
-    match client.mqtt_client {
-        Some(ref c) => Ok(c),
+/// Runs `f` with the MQTT client, connecting to the broker of a synced node first if needed.
+fn with_mqtt_client<T>(client: &Client, f: impl FnOnce(&MqttClient) -> Result<T>) -> Result<T> {
+    let mut mqtt_client_guard = client.mqtt_client.lock().unwrap();
+    match *mqtt_client_guard {
+        Some(ref c) => f(c),
         None => {
-            for node in client.sync.read().unwrap().iter() {
-                let uri = match client.broker_options.use_ws {
-                    true => format!(
-                        "{}://{}:{}/mqtt",
-                        if node.scheme() == "https" { "wss" } else { "ws" },
-                        node.host_str().unwrap(),
-                        node.port_or_known_default().unwrap()
-                    ),
-                    false => format!("tcp://{}", node.host_str().unwrap(),),
-                };
+            for node in client.node_manager.synced_nodes() {
                 let mqtt_options = CreateOptionsBuilder::new()
-                    .server_uri(uri)
+                    .server_uri(mqtt_uri(&node, client.broker_options.use_ws))
                     .client_id("iota.rs")
                     .finalize();
                 let mut mqtt_client = MqttClient::new(mqtt_options)?;
-                let conn_opts = ConnectOptionsBuilder::new()
+                let mut conn_opts = ConnectOptionsBuilder::new();
+                conn_opts
                     .keep_alive_interval(Duration::from_secs(20))
                     .mqtt_version(MQTT_VERSION_3_1_1)
                     .clean_session(true)
                     .connect_timeout(client.broker_options.timeout)
-                    .ssl_options(SslOptions::new())
-                    .finalize();
+                    .ssl_options(SslOptions::new());
+                if let Some((name, password)) = client.node_manager.auth(&node).as_ref().and_then(mqtt_credentials) {
+                    conn_opts.user_name(name).password(password);
+                }
+                let conn_opts = conn_opts.finalize();
 
                 if mqtt_client.connect(conn_opts).is_ok() {
                     poll_mqtt(client.mqtt_topic_handlers.clone(), &mut mqtt_client);
-                    client.mqtt_client = Some(mqtt_client);
+                    *mqtt_client_guard = Some(mqtt_client);
                     break;
                 }
             }
-            client.mqtt_client.as_ref().ok_or(crate::Error::MqttConnectionNotFound)
+            f(mqtt_client_guard.as_ref().ok_or(crate::Error::MqttConnectionNotFound)?)
         }
     }
 }
 
+/// The user name and password of the MQTT connection, the JWT takes precedence over the basic authentication like
+/// for REST requests.
+fn mqtt_credentials(auth: &NodeAuth) -> Option<(&str, &str)> {
+    match (&auth.jwt, &auth.basic_auth_name_pwd) {
+        // The broker only checks the JWT sent as password
+        (Some(jwt), _) => Some((JWT_USER_NAME, jwt)),
+        (None, Some((name, password))) => Some((name, password)),
+        (None, None) => None,
+    }
+}
+
+/// The MQTT broker URI of a node; the websocket endpoint is served at `/mqtt` under the path of the node URL.
+fn mqtt_uri(node: &Url, use_ws: bool) -> String {
+    match use_ws {
+        true => format!(
+            "{}://{}:{}{}",
+            if node.scheme() == "https" { "wss" } else { "ws" },
+            node.host_str().unwrap(),
+            node.port_or_known_default().unwrap(),
+            join_path(node, "mqtt").path()
+        ),
+        false => format!("tcp://{}", node.host_str().unwrap()),
+    }
+}
+
 fn poll_mqtt(mqtt_topic_handlers: Arc<RwLock<TopicHandlerMap>>, client: &mut MqttClient) {
     let receiver = client.start_consuming();
     std::thread::spawn(move || {
@@ -127,12 +154,12 @@ fn poll_mqtt(mqtt_topic_handlers: Arc<RwLock<TopicHandlerMap>>, client: &mut Mqt
 
 /// MQTT subscriber.
 pub struct MqttManager<'a> {
-    client: &'a mut Client,
+    client: &'a Client,
 }
 
 impl<'a> MqttManager<'a> {
     /// Initializes a new instance of the mqtt subscriber.
-    pub fn new(client: &'a mut Client) -> Self {
+    pub fn new(client: &'a Client) -> Self {
         Self { client }
     }
 
@@ -155,10 +182,10 @@ impl<'a> MqttManager<'a> {
     /// This will clear the stored topic handlers and close the MQTT connection.
     pub async fn disconnect(self) -> Result<()> {
         let timeout = self.client.broker_options.timeout;
-        if let Some(client) = &self.client.mqtt_client {
+        let mqtt_client = self.client.mqtt_client.lock().unwrap().take();
+        if let Some(client) = mqtt_client {
             let disconnect_options = DisconnectOptionsBuilder::new().timeout(timeout).finalize();
             client.disconnect(disconnect_options)?;
-            self.client.mqtt_client = None;
 
             {
                 let mqtt_topic_handlers = &self.client.mqtt_topic_handlers;
@@ -174,13 +201,13 @@ impl<'a> MqttManager<'a> {
 /// The MQTT topic manager.
 /// Subscribes and unsubscribes from topics.
 pub struct MqttTopicManager<'a> {
-    client: &'a mut Client,
+    client: &'a Client,
     topics: Vec<Topic>,
 }
 
 impl<'a> MqttTopicManager<'a> {
     /// Initializes a new instance of the mqtt topic manager.
-    fn new(client: &'a mut Client) -> Self {
+    fn new(client: &'a Client) -> Self {
         Self { client, topics: vec![] }
     }
 
@@ -197,16 +224,15 @@ impl<'a> MqttTopicManager<'a> {
     }
 
     /// Subscribe to the given topics with the callback.
-    pub async fn subscribe<C: Fn(&crate::client::TopicEvent) + Send + Sync + 'static>(
-        mut self,
-        callback: C,
-    ) -> Result<()> {
-        let client = get_mqtt_client(&mut self.client)?;
+    pub async fn subscribe<C: Fn(&crate::client::TopicEvent) + Send + Sync + 'static>(self, callback: C) -> Result<()> {
         let cb = Arc::new(Box::new(callback) as Box<dyn Fn(&crate::client::TopicEvent) + Send + Sync + 'static>);
-        client.subscribe_many(
-            &self.topics.iter().map(|t| t.0.clone()).collect::<Vec<String>>(),
-            &vec![1; self.topics.len()],
-        )?;
+        with_mqtt_client(self.client, |client| {
+            client.subscribe_many(
+                &self.topics.iter().map(|t| t.0.clone()).collect::<Vec<String>>(),
+                &vec![1; self.topics.len()],
+            )?;
+            Ok(())
+        })?;
         {
             let mqtt_topic_handlers = &self.client.mqtt_topic_handlers;
             let mut mqtt_topic_handlers = mqtt_topic_handlers.write().await;
@@ -235,7 +261,7 @@ impl<'a> MqttTopicManager<'a> {
             }
         };
 
-        if let Some(client) = &self.client.mqtt_client {
+        if let Some(client) = &*self.client.mqtt_client.lock().unwrap() {
             client.unsubscribe_many(&topics.iter().map(|t| t.0.clone()).collect::<Vec<String>>())?;
         }
 
@@ -255,3 +281,31 @@ impl<'a> MqttTopicManager<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mqtt_credentials_use_one_scheme() {
+        let auth = NodeAuth::new(Some("token"), Some(("name", "password")));
+        assert_eq!(mqtt_credentials(&auth), Some((JWT_USER_NAME, "token")));
+        let auth = NodeAuth::new(None, Some(("name", "password")));
+        assert_eq!(mqtt_credentials(&auth), Some(("name", "password")));
+        assert_eq!(mqtt_credentials(&NodeAuth::default()), None);
+    }
+
+    #[test]
+    fn mqtt_uri_keeps_node_path_prefix() {
+        let uri = |node: &str, use_ws: bool| mqtt_uri(&Url::parse(node).unwrap(), use_ws);
+        assert_eq!(uri("http://node:14265", true), "ws://node:14265/mqtt");
+        assert_eq!(
+            uri("https://gateway.example.com/iota/", true),
+            "wss://gateway.example.com:443/iota/mqtt"
+        );
+        assert_eq!(
+            uri("https://gateway.example.com/iota", false),
+            "tcp://gateway.example.com"
+        );
+    }
+}
@@ -0,0 +1,1013 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Node selection on top of the synced node pool
+
+use crate::{Error, Result};
+use chrono::{DateTime, Utc};
+use reqwest::{header::RETRY_AFTER, RequestBuilder, Response, Url};
+use tokio::sync::{
+    broadcast::{channel, Receiver, Sender},
+    Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard, OwnedSemaphorePermit, Semaphore,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+// Latency assumed for nodes without any measurement, so they are neither preferred nor starved
+const DEFAULT_LATENCY: Duration = Duration::from_millis(500);
+// Weight of a new latency sample in the moving average
+const LATENCY_SMOOTHING: f64 = 0.3;
+// Lowest score a node can get, so every synced node keeps a chance to be picked
+const MIN_SCORE: f64 = 0.01;
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(200);
+const DEFAULT_QUARANTINE_DURATION: Duration = Duration::from_secs(30);
+// Amount of node events buffered for slow receivers
+const NODE_EVENTS_CAPACITY: usize = 64;
+const DEFAULT_RETRYABLE_STATUS_CODES: [u16; 4] = [500, 502, 503, 504];
+pub(crate) const DEFAULT_MAX_MILESTONE_LAG: u32 = 5;
+pub(crate) const DEFAULT_QUORUM_SIZE: usize = 3;
+pub(crate) const DEFAULT_QUORUM_THRESHOLD: usize = 66;
+
+/// Strategy used to select a node from the synced node pool for each request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeSelectionStrategy {
+    /// Cycle through the synced nodes one after another.
+    RoundRobin,
+    /// Use the node with the fewest consecutive errors and the lowest average latency.
+    LowestLatency,
+    /// Pick a random node, weighted by its health score.
+    WeightedRandom,
+}
+
+impl Default for NodeSelectionStrategy {
+    fn default() -> Self {
+        Self::RoundRobin
+    }
+}
+
+/// Credentials sent with the requests to a node.
+///
+/// Only one scheme is used: the JWT when it's set, otherwise the basic authentication. REST requests send the JWT
+/// as bearer token, the MQTT connection sends it as password with the user name `jwt`.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeAuth {
+    /// JSON Web Token. Takes precedence over the basic authentication.
+    pub jwt: Option<String>,
+    /// Name and password for the basic authentication.
+    pub basic_auth_name_pwd: Option<(String, String)>,
+}
+
+impl std::fmt::Debug for NodeAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeAuth")
+            .field("jwt", &self.jwt.as_ref().map(|_| "<redacted>"))
+            .field(
+                "basic_auth_name_pwd",
+                &self.basic_auth_name_pwd.as_ref().map(|(name, _)| (name, "<redacted>")),
+            )
+            .finish()
+    }
+}
+
+impl NodeAuth {
+    /// Creates the credentials from a JSON Web Token and/or a name and password for the basic authentication.
+    pub(crate) fn new(jwt: Option<&str>, basic_auth_name_pwd: Option<(&str, &str)>) -> Self {
+        Self {
+            jwt: jwt.map(|jwt| jwt.to_string()),
+            basic_auth_name_pwd: basic_auth_name_pwd.map(|(name, password)| (name.to_string(), password.to_string())),
+        }
+    }
+
+    /// Adds the credentials to a REST request.
+    pub(crate) fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match (&self.jwt, &self.basic_auth_name_pwd) {
+            (Some(jwt), _) => request.bearer_auth(jwt),
+            (None, Some((name, password))) => request.basic_auth(name, Some(password)),
+            (None, None) => request,
+        }
+    }
+}
+
+/// Policy to retry failed requests on other nodes of the synced node pool.
+/// Posted messages are not retried after a timeout, since the node might have attached them already.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub(crate) max_attempts: usize,
+    pub(crate) backoff: Duration,
+    pub(crate) retryable_status_codes: HashSet<u16>,
+    pub(crate) quarantine_duration: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff: DEFAULT_BACKOFF,
+            retryable_status_codes: DEFAULT_RETRYABLE_STATUS_CODES.iter().copied().collect(),
+            quarantine_duration: DEFAULT_QUARANTINE_DURATION,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates the default retry policy.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Disables retries, every request is sent only once.
+    pub fn disabled() -> Self {
+        Self::new().max_attempts(1)
+    }
+
+    /// Sets how many times a request is sent at most, including the first attempt.
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry, it doubles with every further retry.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the response status codes for which the request is retried on another node.
+    /// Timeouts and connection errors are always retried.
+    pub fn retryable_status_codes(mut self, status_codes: &[u16]) -> Self {
+        self.retryable_status_codes = status_codes.iter().copied().collect();
+        self
+    }
+
+    /// Sets for how long a failing node is skipped by the node selection.
+    pub fn quarantine_duration(mut self, quarantine_duration: Duration) -> Self {
+        self.quarantine_duration = quarantine_duration;
+        self
+    }
+
+    /// Whether a response with the given status code should be retried.
+    pub(crate) fn is_retryable_status(&self, status: u16) -> bool {
+        self.retryable_status_codes.contains(&status)
+    }
+
+    /// Delay before the given retry, starting at 1.
+    pub(crate) fn backoff_delay(&self, retry: usize) -> Duration {
+        self.backoff * 2u32.saturating_pow(retry.saturating_sub(1) as u32)
+    }
+}
+
+/// Limits of the requests sent to each node, to stay below the rate limits of public nodes.
+/// Responses with status 429 are always honoured: the node is skipped by the node selection until its `Retry-After`
+/// elapsed, at most for the quarantine duration of the retry policy.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestLimits {
+    pub(crate) max_requests_per_second: Option<u32>,
+    pub(crate) max_in_flight_requests: Option<usize>,
+}
+
+impl RequestLimits {
+    /// Creates request limits without any limit.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets how many requests are sent to a node per second at most, further requests wait for their turn.
+    pub fn max_requests_per_second(mut self, max_requests_per_second: u32) -> Self {
+        self.max_requests_per_second = Some(max_requests_per_second.max(1));
+        self
+    }
+
+    /// Sets how many requests to a node can be in flight at the same time, further requests wait for a free slot.
+    pub fn max_in_flight_requests(mut self, max_in_flight_requests: usize) -> Self {
+        self.max_in_flight_requests = Some(max_in_flight_requests.max(1));
+        self
+    }
+}
+
+/// Request limiter state of a node.
+#[derive(Debug)]
+struct NodeLimiter {
+    /// Slots of the requests in flight
+    in_flight: Option<Arc<Semaphore>>,
+    /// Earliest time the next request can be sent
+    next_request: Instant,
+}
+
+/// Returns the delay requested by the `Retry-After` header of a response, capped at `max`.
+pub(crate) fn retry_after(response: &Response, max: Duration) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, Utc::now()).map(|delay| delay.min(max))
+}
+
+/// Parses a `Retry-After` value, given either in seconds or as HTTP date.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past doesn't delay the requests
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// Quorum for read requests, which are sent to multiple nodes and only accepted if enough of them agree.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Quorum {
+    /// Amount of nodes queried for every request
+    pub(crate) size: usize,
+    /// Percentage of the queried nodes that need to return the same response
+    pub(crate) threshold: usize,
+}
+
+impl Quorum {
+    /// Returns the response `data` of the most nodes if they reach the threshold. Nodes that responded with the same
+    /// error status agree too, so the error is returned if enough of them agree on it, like a 404 for an unknown
+    /// output. Nodes whose request failed otherwise don't agree with any node.
+    pub(crate) fn agreed_data(&self, responses: Vec<(Url, Result<serde_json::Value>)>) -> Result<serde_json::Value> {
+        // Group the nodes by their response
+        let mut agreements: Vec<(Result<serde_json::Value>, Vec<Url>)> = Vec::new();
+        let mut failed_nodes = Vec::new();
+        for (node, response) in responses {
+            if let Err(error) = &response {
+                if !matches!(error, Error::ResponseError(..)) {
+                    failed_nodes.push(node);
+                    continue;
+                }
+            }
+            match agreements
+                .iter_mut()
+                .find(|(agreed_response, _)| same_response(agreed_response, &response))
+            {
+                Some((_, agreeing_nodes)) => agreeing_nodes.push(node),
+                None => agreements.push((response, vec![node])),
+            }
+        }
+        agreements.sort_by_key(|(_, agreeing_nodes)| std::cmp::Reverse(agreeing_nodes.len()));
+
+        let mut agreements = agreements.into_iter();
+        let agreeing_nodes = match agreements.next() {
+            Some((response, agreeing_nodes)) if agreeing_nodes.len() * 100 >= self.size * self.threshold => {
+                return response;
+            }
+            Some((_, agreeing_nodes)) => agreeing_nodes.len(),
+            None => 0,
+        };
+        let disagreeing_nodes = agreements
+            .flat_map(|(_, nodes)| nodes)
+            .chain(failed_nodes)
+            .map(|node| node.to_string())
+            .collect();
+        Err(Error::QuorumThresholdError(
+            agreeing_nodes,
+            self.size,
+            disagreeing_nodes,
+        ))
+    }
+}
+
+/// Whether two quorum responses agree: the same data or the same error status.
+fn same_response(a: &Result<serde_json::Value>, b: &Result<serde_json::Value>) -> bool {
+    match (a, b) {
+        (Ok(a), Ok(b)) => a == b,
+        (Err(Error::ResponseError(a, _)), Err(Error::ResponseError(b, _))) => a == b,
+        _ => false,
+    }
+}
+
+/// Latency and error statistics of a node, gathered while syncing and on every request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeStats {
+    /// Moving average of the response latency.
+    pub latency: Option<Duration>,
+    /// Amount of requests sent to the node.
+    pub requests: u64,
+    /// Amount of failed requests.
+    pub errors: u64,
+    /// Amount of failed requests since the last successful one.
+    pub consecutive_errors: u32,
+}
+
+impl NodeStats {
+    /// Records a successful request with its latency.
+    pub(crate) fn record_success(&mut self, latency: Duration) {
+        self.requests += 1;
+        self.consecutive_errors = 0;
+        self.latency = Some(match self.latency {
+            Some(average) => average.mul_f64(1.0 - LATENCY_SMOOTHING) + latency.mul_f64(LATENCY_SMOOTHING),
+            None => latency,
+        });
+    }
+
+    /// Records a failed request.
+    pub(crate) fn record_error(&mut self) {
+        self.requests += 1;
+        self.errors += 1;
+        self.consecutive_errors += 1;
+    }
+
+    /// Health score of the node, higher is better.
+    /// Fast nodes with a low error rate get the highest scores.
+    pub fn score(&self) -> f64 {
+        let latency_ms = self.latency.unwrap_or(DEFAULT_LATENCY).as_secs_f64() * 1000.0;
+        let error_rate = match self.requests {
+            0 => 0.0,
+            requests => self.errors as f64 / requests as f64,
+        };
+        let score = (1.0 - error_rate) / (1.0 + latency_ms / 100.0) / (1 + self.consecutive_errors) as f64;
+        score.max(MIN_SCORE)
+    }
+}
+
+/// Last sync result of a node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStatus {
+    /// URL of the node.
+    pub url: Url,
+    /// Whether the node is in the synced node pool.
+    pub synced: bool,
+    /// Whether the node reported itself as healthy.
+    pub healthy: bool,
+    /// Network ID of the node.
+    pub network_id: Option<String>,
+    /// Latest milestone index known by the node.
+    pub latest_milestone_index: Option<u32>,
+    /// Solid milestone index of the node.
+    pub solid_milestone_index: Option<u32>,
+    /// Latency of the info request.
+    pub latency: Option<Duration>,
+    /// Whether the node supports remote PoW.
+    pub pow: bool,
+    /// Error of the info request, if it failed.
+    pub last_error: Option<String>,
+    /// Statistics of all requests to the node.
+    pub stats: NodeStats,
+}
+
+impl NodeStatus {
+    /// Creates the status of a node that couldn't be synced yet.
+    pub(crate) fn new(url: Url) -> Self {
+        Self {
+            url,
+            synced: false,
+            healthy: false,
+            network_id: None,
+            latest_milestone_index: None,
+            solid_milestone_index: None,
+            latency: None,
+            pow: false,
+            last_error: None,
+            stats: Default::default(),
+        }
+    }
+}
+
+/// Event emitted when the synced node pool changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeEvent {
+    /// The node joined the synced node pool.
+    Joined(Url),
+    /// The node left the synced node pool.
+    Left(Url),
+}
+
+/// Selects the node used for a request from the synced node pool.
+#[derive(Debug, Clone)]
+pub(crate) struct NodeManager {
+    /// Configured nodes, checked by the node syncing
+    nodes: Arc<RwLock<HashSet<Url>>>,
+    /// Held during a node syncing, so a sync can't overwrite the result of a later one
+    sync_lock: Arc<AsyncMutex<()>>,
+    /// Node pool of synced IOTA nodes
+    pub(crate) synced_nodes: Arc<RwLock<HashSet<Url>>>,
+    /// Statistics of every node that has been requested
+    pub(crate) stats: Arc<RwLock<HashMap<Url, NodeStats>>>,
+    /// Failing nodes and until when they are skipped by the node selection
+    quarantined: Arc<RwLock<HashMap<Url, Instant>>>,
+    /// Last sync result of every node
+    status: Arc<RwLock<HashMap<Url, NodeStatus>>>,
+    /// Sender of the synced node pool changes
+    events: Sender<NodeEvent>,
+    /// Synced nodes that support remote PoW
+    pow_nodes: Arc<RwLock<HashSet<Url>>>,
+    /// Credentials of the nodes that require authentication
+    auth: Arc<RwLock<HashMap<Url, NodeAuth>>>,
+    /// Node always used when it's healthy
+    primary_node: Option<Url>,
+    /// Node always used for remote PoW when it's healthy, and only for remote PoW
+    primary_pow_node: Option<Url>,
+    /// How many milestones a node can be behind the other nodes before it's removed from the synced node pool
+    pub(crate) max_milestone_lag: u32,
+    /// Limits of the requests sent to each node
+    request_limits: RequestLimits,
+    /// Request limiter state of every node that has been requested
+    limiters: Arc<RwLock<HashMap<Url, NodeLimiter>>>,
+    strategy: NodeSelectionStrategy,
+    round_robin_index: Arc<AtomicUsize>,
+}
+
+impl NodeManager {
+    /// Creates a node manager for the given synced node pool, all nodes are assumed to support remote PoW.
+    pub(crate) fn new(synced_nodes: HashSet<Url>, strategy: NodeSelectionStrategy) -> Self {
+        Self {
+            nodes: Default::default(),
+            sync_lock: Default::default(),
+            pow_nodes: Arc::new(RwLock::new(synced_nodes.clone())),
+            synced_nodes: Arc::new(RwLock::new(synced_nodes)),
+            stats: Default::default(),
+            status: Default::default(),
+            events: channel(NODE_EVENTS_CAPACITY).0,
+            quarantined: Default::default(),
+            auth: Default::default(),
+            primary_node: None,
+            primary_pow_node: None,
+            max_milestone_lag: DEFAULT_MAX_MILESTONE_LAG,
+            request_limits: Default::default(),
+            limiters: Default::default(),
+            strategy,
+            round_robin_index: Default::default(),
+        }
+    }
+
+    /// Sets the configured nodes.
+    pub(crate) fn with_nodes(self, nodes: HashSet<Url>) -> Self {
+        *self.nodes.write().unwrap() = nodes;
+        self
+    }
+
+    /// Returns the configured nodes.
+    pub(crate) fn nodes(&self) -> HashSet<Url> {
+        self.nodes.read().unwrap().clone()
+    }
+
+    /// Adds a configured node, it joins the synced node pool with the next sync.
+    pub(crate) fn add_node(&self, node: Url) {
+        self.nodes.write().unwrap().insert(node);
+    }
+
+    /// Removes a configured node and removes it from the synced node pool.
+    pub(crate) fn remove_node(&self, node: &Url) {
+        self.nodes.write().unwrap().remove(node);
+        self.retain_configured_nodes();
+    }
+
+    /// Replaces the configured nodes, the nodes that aren't configured anymore are removed from the synced node pool.
+    pub(crate) fn replace_nodes(&self, nodes: HashSet<Url>) {
+        *self.nodes.write().unwrap() = nodes;
+        self.retain_configured_nodes();
+    }
+
+    /// Removes the nodes that aren't configured anymore from the synced node pool and forgets everything about them,
+    /// so a node that is added again doesn't get its previous credentials, statistics or quarantine.
+    fn retain_configured_nodes(&self) {
+        let nodes = self.nodes();
+        self.status.write().unwrap().retain(|node, _| nodes.contains(node));
+        self.auth.write().unwrap().retain(|node, _| nodes.contains(node));
+        self.stats.write().unwrap().retain(|node, _| nodes.contains(node));
+        self.quarantined.write().unwrap().retain(|node, _| nodes.contains(node));
+        self.limiters.write().unwrap().retain(|node, _| nodes.contains(node));
+
+        let synced_nodes = self.synced_nodes.read().unwrap().clone();
+        let pow_nodes = self.pow_nodes.read().unwrap().clone();
+        self.set_synced_nodes(synced_nodes, pow_nodes);
+    }
+
+    /// Waits until no other node syncing runs, the sync runs until the returned guard is dropped.
+    pub(crate) async fn lock_sync(&self) -> AsyncMutexGuard<'_, ()> {
+        self.sync_lock.lock().await
+    }
+
+    /// Sets the credentials of a node that requires authentication.
+    pub(crate) fn set_auth(&self, node: Url, auth: NodeAuth) {
+        self.auth.write().unwrap().insert(node, auth);
+    }
+
+    /// Sets the credentials of the nodes that require authentication.
+    pub(crate) fn with_auth(self, auth: HashMap<Url, NodeAuth>) -> Self {
+        *self.auth.write().unwrap() = auth;
+        self
+    }
+
+    /// Sets the nodes preferred for all requests and for remote PoW requests.
+    pub(crate) fn with_primary_nodes(mut self, primary_node: Option<Url>, primary_pow_node: Option<Url>) -> Self {
+        self.primary_node = primary_node;
+        self.primary_pow_node = primary_pow_node;
+        self
+    }
+
+    /// Sets how many milestones a node can be behind the other nodes before it's removed from the synced node pool.
+    pub(crate) fn with_max_milestone_lag(mut self, max_milestone_lag: u32) -> Self {
+        self.max_milestone_lag = max_milestone_lag;
+        self
+    }
+
+    /// Sets the limits of the requests sent to each node.
+    pub(crate) fn with_request_limits(mut self, request_limits: RequestLimits) -> Self {
+        self.request_limits = request_limits;
+        self
+    }
+
+    /// Waits until a request can be sent to the node according to the request limits.
+    /// The returned permit has to be kept while the request is in flight.
+    pub(crate) async fn acquire_request_slot(&self, node: &Url) -> Option<OwnedSemaphorePermit> {
+        let in_flight = self.limiter(node, |limiter| limiter.in_flight.clone());
+        let permit = match in_flight {
+            Some(in_flight) => Some(in_flight.acquire_owned().await.expect("semaphore is never closed")),
+            None => None,
+        };
+        let slot = self.reserve_request_slot(node);
+        if slot > Instant::now() {
+            tokio::time::sleep_until(slot.into()).await;
+        }
+        permit
+    }
+
+    /// Reserves the time the next request to the node can be sent at.
+    fn reserve_request_slot(&self, node: &Url) -> Instant {
+        let max_requests_per_second = self.request_limits.max_requests_per_second;
+        self.limiter(node, |limiter| {
+            let slot = limiter.next_request.max(Instant::now());
+            if let Some(max_requests_per_second) = max_requests_per_second {
+                limiter.next_request = slot + Duration::from_secs(1) / max_requests_per_second;
+            }
+            slot
+        })
+    }
+
+    fn limiter<T>(&self, node: &Url, f: impl FnOnce(&mut NodeLimiter) -> T) -> T {
+        let max_in_flight_requests = self.request_limits.max_in_flight_requests;
+        let mut limiters = self.limiters.write().unwrap();
+        let limiter = limiters.entry(node.clone()).or_insert_with(|| NodeLimiter {
+            in_flight: max_in_flight_requests.map(|max| Arc::new(Semaphore::new(max))),
+            next_request: Instant::now(),
+        });
+        f(limiter)
+    }
+
+    /// Returns the credentials of a node, if it requires authentication.
+    pub(crate) fn auth(&self, node: &Url) -> Option<NodeAuth> {
+        self.auth.read().unwrap().get(node).cloned()
+    }
+
+    /// Replaces the synced node pool and the synced nodes that support remote PoW.
+    /// Only configured nodes are kept, so a node removed while it was synced doesn't join the pool again.
+    /// An event is sent for every node that joined or left the pool.
+    pub(crate) fn set_synced_nodes(&self, synced_nodes: HashSet<Url>, pow_nodes: HashSet<Url>) {
+        // Hold the configured nodes until the pool is replaced, so they can't be removed in the meantime
+        let nodes = self.nodes.read().unwrap();
+        let synced_nodes: HashSet<Url> = synced_nodes.intersection(&nodes).cloned().collect();
+        let pow_nodes = pow_nodes.intersection(&nodes).cloned().collect();
+        let previous_nodes = std::mem::replace(&mut *self.synced_nodes.write().unwrap(), synced_nodes.clone());
+        *self.pow_nodes.write().unwrap() = pow_nodes;
+        // Sending only fails if there is no receiver
+        for node in previous_nodes.difference(&synced_nodes) {
+            let _ = self.events.send(NodeEvent::Left(node.clone()));
+        }
+        for node in synced_nodes.difference(&previous_nodes) {
+            let _ = self.events.send(NodeEvent::Joined(node.clone()));
+        }
+    }
+
+    /// Stores the last sync result of a node, if the node is still configured.
+    pub(crate) fn set_status(&self, status: NodeStatus) {
+        if !self.nodes.read().unwrap().contains(&status.url) {
+            return;
+        }
+        self.status.write().unwrap().insert(status.url.clone(), status);
+    }
+
+    /// Returns the last sync result of every node that has been synced, sorted by URL.
+    pub(crate) fn status(&self) -> Vec<NodeStatus> {
+        let synced_nodes = self.synced_nodes.read().unwrap();
+        let stats = self.stats.read().unwrap();
+        let mut status: Vec<NodeStatus> = self
+            .status
+            .read()
+            .unwrap()
+            .values()
+            .cloned()
+            .map(|mut status| {
+                status.synced = synced_nodes.contains(&status.url);
+                status.stats = stats.get(&status.url).cloned().unwrap_or_default();
+                status
+            })
+            .collect();
+        status.sort_by(|a, b| a.url.cmp(&b.url));
+        status
+    }
+
+    /// Subscribes to the changes of the synced node pool.
+    pub(crate) fn subscribe(&self) -> Receiver<NodeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Returns the synced nodes in a stable order.
+    pub(crate) fn synced_nodes(&self) -> Vec<Url> {
+        let mut nodes: Vec<Url> = self.synced_nodes.read().unwrap().iter().cloned().collect();
+        nodes.sort();
+        nodes
+    }
+
+    /// Records a successful request to a node.
+    pub(crate) fn record_success(&self, node: &Url, latency: Duration) {
+        self.stats
+            .write()
+            .unwrap()
+            .entry(node.clone())
+            .or_default()
+            .record_success(latency);
+    }
+
+    /// Records a failed request to a node.
+    pub(crate) fn record_error(&self, node: &Url) {
+        self.stats
+            .write()
+            .unwrap()
+            .entry(node.clone())
+            .or_default()
+            .record_error();
+    }
+
+    /// Skips a failing node in the node selection for the given duration.
+    pub(crate) fn quarantine(&self, node: &Url, duration: Duration) {
+        self.quarantined
+            .write()
+            .unwrap()
+            .insert(node.clone(), Instant::now() + duration);
+    }
+
+    /// Whether a node is currently skipped by the node selection.
+    pub(crate) fn is_quarantined(&self, node: &Url) -> bool {
+        match self.quarantined.read().unwrap().get(node) {
+            Some(until) => *until > Instant::now(),
+            None => false,
+        }
+    }
+
+    /// Selects a node from the synced node pool.
+    /// The primary node is used when it's healthy, otherwise a node is picked with the configured strategy. The
+    /// primary PoW node is only used for remote PoW requests.
+    pub(crate) fn select_node(&self, excluded: &[Url]) -> Option<Url> {
+        let nodes = self
+            .synced_nodes()
+            .into_iter()
+            .filter(|node| self.primary_pow_node.as_ref() != Some(node) || self.primary_node.as_ref() == Some(node))
+            .collect();
+        self.select_from(nodes, self.primary_node.as_ref(), excluded)
+    }
+
+    /// Selects a node that supports remote PoW from the synced node pool.
+    /// The primary PoW node is used when it's healthy, otherwise a node is picked with the configured strategy.
+    pub(crate) fn select_pow_node(&self, excluded: &[Url]) -> Option<Url> {
+        let pow_nodes = self.pow_nodes.read().unwrap().clone();
+        let nodes = self
+            .synced_nodes()
+            .into_iter()
+            .filter(|node| pow_nodes.contains(node))
+            .collect();
+        self.select_from(nodes, self.primary_pow_node.as_ref(), excluded)
+    }
+
+    /// Selects one of the given synced nodes, preferring the healthy primary node.
+    /// Nodes that are quarantined or in `excluded` are only used if no other node is left.
+    fn select_from(&self, synced_nodes: Vec<Url>, primary_node: Option<&Url>, excluded: &[Url]) -> Option<Url> {
+        if let Some(primary_node) = primary_node {
+            if synced_nodes.contains(primary_node)
+                && !excluded.contains(primary_node)
+                && !self.is_quarantined(primary_node)
+            {
+                return Some(primary_node.clone());
+            }
+        }
+        let untried_nodes: Vec<Url> = synced_nodes
+            .iter()
+            .filter(|node| !excluded.contains(node))
+            .cloned()
+            .collect();
+        let healthy_nodes: Vec<Url> = untried_nodes
+            .iter()
+            .filter(|node| !self.is_quarantined(node))
+            .cloned()
+            .collect();
+        let nodes = if !healthy_nodes.is_empty() {
+            healthy_nodes
+        } else if !untried_nodes.is_empty() {
+            untried_nodes
+        } else {
+            synced_nodes
+        };
+        if nodes.is_empty() {
+            return None;
+        }
+        let stats = self.stats.read().unwrap();
+        let node_stats = |node: &Url| stats.get(node).cloned().unwrap_or_default();
+        let selected = match self.strategy {
+            NodeSelectionStrategy::RoundRobin => {
+                let index = self.round_robin_index.fetch_add(1, Ordering::Relaxed);
+                &nodes[index % nodes.len()]
+            }
+            NodeSelectionStrategy::LowestLatency => nodes
+                .iter()
+                .min_by_key(|node| {
+                    let stats = node_stats(node);
+                    (stats.consecutive_errors, stats.latency.unwrap_or(DEFAULT_LATENCY))
+                })
+                .expect("node pool is not empty"),
+            NodeSelectionStrategy::WeightedRandom => {
+                let scores: Vec<f64> = nodes.iter().map(|node| node_stats(node).score()).collect();
+                let mut target = rand::random::<f64>() * scores.iter().sum::<f64>();
+                let mut selected = &nodes[nodes.len() - 1];
+                for (node, score) in nodes.iter().zip(scores) {
+                    if target < score {
+                        selected = node;
+                        break;
+                    }
+                    target -= score;
+                }
+                selected
+            }
+        };
+        Some(selected.clone())
+    }
+}
+
+/// Joins an API path onto the URL of a node, keeping the path prefix of the node URL.
+pub(crate) fn join_path(node: &Url, path: &str) -> Url {
+    let mut url = node.clone();
+    url.set_path(&format!(
+        "{}/{}",
+        node.path().trim_end_matches('/'),
+        path.trim_start_matches('/')
+    ));
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_manager(strategy: NodeSelectionStrategy) -> NodeManager {
+        let nodes: HashSet<Url> = ["http://node-a:14265", "http://node-b:14265", "http://node-c:14265"]
+            .iter()
+            .map(|url| Url::parse(url).unwrap())
+            .collect();
+        NodeManager::new(nodes.clone(), strategy).with_nodes(nodes)
+    }
+
+    #[test]
+    fn round_robin_cycles_through_nodes() {
+        let manager = node_manager(NodeSelectionStrategy::RoundRobin);
+        let selected: Vec<Url> = (0..6).map(|_| manager.select_node(&[]).unwrap()).collect();
+        assert_eq!(selected[..3], manager.synced_nodes()[..]);
+        assert_eq!(selected[..3], selected[3..]);
+    }
+
+    #[test]
+    fn lowest_latency_prefers_fast_and_healthy_nodes() {
+        let manager = node_manager(NodeSelectionStrategy::LowestLatency);
+        let nodes = manager.synced_nodes();
+        manager.record_success(&nodes[0], Duration::from_millis(300));
+        manager.record_success(&nodes[1], Duration::from_millis(50));
+        manager.record_success(&nodes[2], Duration::from_millis(100));
+        assert_eq!(manager.select_node(&[]).unwrap(), nodes[1]);
+
+        manager.record_error(&nodes[1]);
+        assert_eq!(manager.select_node(&[]).unwrap(), nodes[2]);
+    }
+
+    #[test]
+    fn quarantined_and_excluded_nodes_are_skipped() {
+        let manager = node_manager(NodeSelectionStrategy::LowestLatency);
+        let nodes = manager.synced_nodes();
+        manager.quarantine(&nodes[0], Duration::from_secs(60));
+        assert_eq!(manager.select_node(&[]).unwrap(), nodes[1]);
+        assert_eq!(manager.select_node(&[nodes[1].clone()]).unwrap(), nodes[2]);
+        // falls back to quarantined and excluded nodes when nothing else is left
+        assert_eq!(manager.select_node(&nodes[1..]).unwrap(), nodes[0]);
+        assert_eq!(manager.select_node(&nodes).unwrap(), nodes[0]);
+    }
+
+    #[test]
+    fn retry_backoff_doubles() {
+        let policy = RetryPolicy::new().backoff(Duration::from_millis(100));
+        assert_eq!(policy.backoff_delay(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_delay(3), Duration::from_millis(400));
+        assert!(policy.is_retryable_status(503));
+        assert!(!policy.is_retryable_status(404));
+    }
+
+    #[test]
+    fn score_drops_with_errors() {
+        let mut stats = NodeStats::default();
+        stats.record_success(Duration::from_millis(100));
+        let healthy_score = stats.score();
+        stats.record_error();
+        assert!(stats.score() < healthy_score);
+        assert!(stats.score() >= MIN_SCORE);
+    }
+
+    #[test]
+    fn empty_pool_has_no_node() {
+        let manager = NodeManager::new(HashSet::new(), NodeSelectionStrategy::WeightedRandom);
+        assert!(manager.select_node(&[]).is_none());
+    }
+
+    #[test]
+    fn primary_node_is_preferred_while_healthy() {
+        let primary_node = Url::parse("http://node-b:14265").unwrap();
+        let manager =
+            node_manager(NodeSelectionStrategy::RoundRobin).with_primary_nodes(Some(primary_node.clone()), None);
+        for _ in 0..3 {
+            assert_eq!(manager.select_node(&[]), Some(primary_node.clone()));
+        }
+        manager.quarantine(&primary_node, Duration::from_secs(60));
+        assert_ne!(manager.select_node(&[]), Some(primary_node));
+    }
+
+    #[test]
+    fn pow_requests_only_use_pow_nodes() {
+        let pow_node = Url::parse("http://node-c:14265").unwrap();
+        let manager = node_manager(NodeSelectionStrategy::RoundRobin).with_primary_nodes(None, Some(pow_node.clone()));
+        let synced_nodes = manager.synced_nodes().into_iter().collect();
+        manager.set_synced_nodes(synced_nodes, std::iter::once(pow_node.clone()).collect());
+        for _ in 0..3 {
+            assert_eq!(manager.select_pow_node(&[]), Some(pow_node.clone()));
+            assert_ne!(manager.select_node(&[]), Some(pow_node.clone()));
+        }
+        manager.set_synced_nodes(manager.synced_nodes().into_iter().collect(), HashSet::new());
+        assert!(manager.select_pow_node(&[]).is_none());
+    }
+
+    #[test]
+    fn pool_changes_are_sent_as_events() {
+        let manager = node_manager(NodeSelectionStrategy::RoundRobin);
+        let mut events = manager.subscribe();
+        let node_a = Url::parse("http://node-a:14265").unwrap();
+        let node_d = Url::parse("http://node-d:14265").unwrap();
+        manager.add_node(node_d.clone());
+        let mut synced_nodes: HashSet<Url> = manager.synced_nodes().into_iter().collect();
+        synced_nodes.remove(&node_a);
+        synced_nodes.insert(node_d.clone());
+        manager.set_synced_nodes(synced_nodes, HashSet::new());
+        assert_eq!(events.try_recv().unwrap(), NodeEvent::Left(node_a));
+        assert_eq!(events.try_recv().unwrap(), NodeEvent::Joined(node_d));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn removed_nodes_leave_the_pool() {
+        let manager = node_manager(NodeSelectionStrategy::RoundRobin);
+        let mut events = manager.subscribe();
+        let node_a = Url::parse("http://node-a:14265").unwrap();
+        manager.remove_node(&node_a);
+        assert_eq!(events.try_recv().unwrap(), NodeEvent::Left(node_a.clone()));
+        assert!(!manager.nodes().contains(&node_a));
+        assert!(!manager.synced_nodes().contains(&node_a));
+        assert_eq!(manager.synced_nodes().len(), 2);
+    }
+
+    #[test]
+    fn quorum_needs_the_threshold_of_agreeing_nodes() {
+        let quorum = Quorum { size: 3, threshold: 66 };
+        let node = |name: &str| Url::parse(&format!("http://{}:14265", name)).unwrap();
+        let balance = |balance: u64| Ok(serde_json::json!({ "balance": balance }));
+        let failed = || {
+            Err(Error::IoError(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "timed out",
+            )))
+        };
+
+        let agreed = quorum.agreed_data(vec![
+            (node("node-a"), balance(10)),
+            (node("node-b"), balance(10)),
+            (node("node-c"), balance(20)),
+        ]);
+        assert_eq!(agreed.unwrap(), balance(10).unwrap());
+
+        let disagreed = quorum.agreed_data(vec![
+            (node("node-a"), balance(10)),
+            (node("node-b"), balance(20)),
+            (node("node-c"), balance(30)),
+        ]);
+        assert!(matches!(disagreed, Err(Error::QuorumThresholdError(1, 3, nodes)) if nodes.len() == 2));
+
+        let some_failed = quorum.agreed_data(vec![
+            (node("node-a"), balance(10)),
+            (node("node-b"), failed()),
+            (node("node-c"), failed()),
+        ]);
+        assert!(matches!(
+            some_failed,
+            Err(Error::QuorumThresholdError(1, 3, nodes)) if nodes == vec!["http://node-b:14265/", "http://node-c:14265/"]
+        ));
+
+        let all_failed = quorum.agreed_data(vec![
+            (node("node-a"), failed()),
+            (node("node-b"), failed()),
+            (node("node-c"), failed()),
+        ]);
+        assert!(matches!(all_failed, Err(Error::QuorumThresholdError(0, 3, nodes)) if nodes.len() == 3));
+    }
+
+    #[test]
+    fn quorum_agrees_on_error_statuses() {
+        let quorum = Quorum { size: 3, threshold: 66 };
+        let node = |name: &str| Url::parse(&format!("http://{}:14265", name)).unwrap();
+        let not_found = || Err(Error::ResponseError(404, "output not found".into()));
+
+        let agreed = quorum.agreed_data(vec![
+            (node("node-a"), not_found()),
+            (node("node-b"), not_found()),
+            (node("node-c"), Ok(serde_json::json!({ "balance": 10 }))),
+        ]);
+        assert!(matches!(agreed, Err(Error::ResponseError(404, _))));
+
+        let disagreed = quorum.agreed_data(vec![
+            (node("node-a"), not_found()),
+            (node("node-b"), Err(Error::ResponseError(500, "internal error".into()))),
+            (node("node-c"), Ok(serde_json::json!({ "balance": 10 }))),
+        ]);
+        assert!(matches!(disagreed, Err(Error::QuorumThresholdError(1, 3, nodes)) if nodes.len() == 2));
+    }
+
+    #[test]
+    fn removed_nodes_are_forgotten() {
+        let manager = node_manager(NodeSelectionStrategy::RoundRobin);
+        let node_a = Url::parse("http://node-a:14265").unwrap();
+        manager.set_auth(node_a.clone(), NodeAuth::new(Some("token"), None));
+        manager.record_error(&node_a);
+        manager.quarantine(&node_a, Duration::from_secs(60));
+        manager.remove_node(&node_a);
+
+        manager.add_node(node_a.clone());
+        assert_eq!(manager.auth(&node_a), None);
+        assert!(!manager.is_quarantined(&node_a));
+        assert!(!manager.stats.read().unwrap().contains_key(&node_a));
+    }
+
+    #[test]
+    fn sync_results_only_keep_configured_nodes() {
+        let manager = node_manager(NodeSelectionStrategy::RoundRobin);
+        // A sync that started before the node was removed
+        let synced_nodes: HashSet<Url> = manager.synced_nodes().into_iter().collect();
+        let node_a = Url::parse("http://node-a:14265").unwrap();
+        manager.remove_node(&node_a);
+        manager.set_synced_nodes(synced_nodes.clone(), synced_nodes);
+        assert!(!manager.synced_nodes().contains(&node_a));
+        assert!(manager.select_pow_node(&[node_a.clone()]).is_some());
+        assert_eq!(manager.synced_nodes().len(), 2);
+    }
+
+    #[test]
+    fn requests_are_spaced_by_the_rate_limit() {
+        let manager = node_manager(NodeSelectionStrategy::RoundRobin)
+            .with_request_limits(RequestLimits::new().max_requests_per_second(10));
+        let node = Url::parse("http://node-a:14265").unwrap();
+        let first = manager.reserve_request_slot(&node);
+        let second = manager.reserve_request_slot(&node);
+        assert_eq!(second - first, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn retry_after_is_parsed_in_seconds_and_as_date() {
+        let now = DateTime::parse_from_rfc2822("Sun, 06 Nov 1994 08:49:37 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after(" 120 ", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:50:07 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:00:00 GMT", now),
+            Some(Duration::from_secs(0))
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn join_path_keeps_node_path_prefix() {
+        let join = |node: &str, path: &str| join_path(&Url::parse(node).unwrap(), path).to_string();
+        assert_eq!(
+            join("http://node:14265", "api/v1/info"),
+            "http://node:14265/api/v1/info"
+        );
+        assert_eq!(join("http://node:14265/", "health"), "http://node:14265/health");
+        assert_eq!(
+            join("https://gateway.example.com/iota", "api/v1/info"),
+            "https://gateway.example.com/iota/api/v1/info"
+        );
+        assert_eq!(
+            join("https://gateway.example.com/iota/", "/api/v1/messages"),
+            "https://gateway.example.com/iota/api/v1/messages"
+        );
+    }
+}
@@ -3,10 +3,12 @@
 
 // These are E2E test samples, so they are ignored by default.
 
+use bee_common::packable::Packable;
 use bee_message::prelude::*;
 use iota_client::Seed;
 
 use bee_rest_api::types::MessageDto;
+use futures::StreamExt;
 use std::{convert::TryFrom, str::FromStr};
 
 const DEFAULT_NODE_URL: &str = "http://0.0.0.0:14265";
@@ -185,7 +187,7 @@ async fn test_get_message_metadata() {
 #[ignore]
 async fn test_get_message_raw() {
     let message_id = setup_indexation_message().await;
-    iota_client::Client::builder()
+    let raw = iota_client::Client::builder()
         .with_node(DEFAULT_NODE_URL)
         .unwrap()
         .finish()
@@ -195,6 +197,8 @@ async fn test_get_message_raw() {
         .raw(&message_id)
         .await
         .unwrap();
+    let message = Message::unpack(&mut raw.as_slice()).unwrap();
+    assert_eq!(message.id().0, message_id);
 }
 
 #[tokio::test]
@@ -250,6 +254,64 @@ async fn test_get_address_outputs() {
     println!("{:#?}", r);
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_get_address_balance_ed25519() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_address()
+        .balance_ed25519(
+            &Ed25519Address::from_str("5eec99d6ee4ba21aa536c3364bbf2b587cb98a7f2565b75d948b10083e2143f8").unwrap(),
+        )
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_address_outputs_ed25519() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_address()
+        .outputs_ed25519(
+            &Ed25519Address::from_str("5eec99d6ee4ba21aa536c3364bbf2b587cb98a7f2565b75d948b10083e2143f8").unwrap(),
+        )
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_address_outputs_query() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_address()
+        .outputs_query(&"atoi1qzt0nhsf38nh6rs4p6zs5knqp6psgha9wsv74uajqgjmwc75ugupx3y7x0r".into())
+        .with_include_spent(true)
+        .with_output_type(0)
+        .finish()
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_get_output() {
@@ -272,6 +334,42 @@ async fn test_get_output() {
     println!("{:#?}", r);
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_get_included_message() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_included_message(
+            &TransactionId::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
+        )
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_transaction_status() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_transaction_status(
+            &TransactionId::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
+        )
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_get_peers() {
@@ -288,6 +386,55 @@ async fn test_get_peers() {
     println!("{:#?}", r);
 }
 
+const PEER_ID: &str = "12D3KooWC7uE9w3RN4Vh1FJAZa8SbE8yMWR6wCVBajcWpyWguV73";
+const PEER_MULTI_ADDRESS: &str = "/ip4/127.0.0.1/tcp/15600/p2p/12D3KooWC7uE9w3RN4Vh1FJAZa8SbE8yMWR6wCVBajcWpyWguV73";
+
+#[tokio::test]
+#[ignore]
+async fn test_add_peer() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .add_peer(DEFAULT_NODE_URL, PEER_MULTI_ADDRESS, Some("test"))
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_peer() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_peer(DEFAULT_NODE_URL, PEER_ID)
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_remove_peer() {
+    iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .remove_peer(DEFAULT_NODE_URL, PEER_ID)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_get_milestone() {
@@ -319,3 +466,103 @@ async fn test_get_milestone_utxo_changes() {
 
     println!("{:#?}", r);
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_walk_milestones() {
+    let client = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap();
+    let indexes: Vec<u32> = client
+        .walk_milestones(1, 10)
+        .resume_from(3)
+        .with_utxo_changes(true)
+        .stream()
+        .map(|walked| walked.unwrap().milestone.index)
+        .collect()
+        .await;
+
+    assert_eq!(indexes, (3..=10).collect::<Vec<u32>>());
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_milestone_payload() {
+    let client = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap();
+    let milestone = client.get_milestone(3).await.unwrap();
+    let r = client.get_milestone_payload(&milestone.message_id).await.unwrap();
+
+    assert_eq!(r.essence().index(), 3);
+    println!("{:#?}", r);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_milestone_payload_not_a_milestone() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_milestone_payload(&setup_indexation_message().await)
+        .await;
+
+    assert!(matches!(r, Err(iota_client::Error::NotAMilestone(_))));
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_receipts() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_receipts()
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_receipts_migrated_at() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_receipts_migrated_at(3)
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_treasury() {
+    let r = iota_client::Client::builder()
+        .with_node(DEFAULT_NODE_URL)
+        .unwrap()
+        .finish()
+        .await
+        .unwrap()
+        .get_treasury()
+        .await
+        .unwrap();
+
+    println!("{:#?}", r);
+}